@@ -5,9 +5,9 @@ use std::{
 };
 
 mod annotatedstringpart;
-use annotatedstringpart::AnnotatedStringPart;
+pub use annotatedstringpart::AnnotatedStringPart;
 mod annotatedstringiterator;
-use annotatedstringiterator::AnnotatedStringIterator;
+pub use annotatedstringiterator::AnnotatedStringIterator;
 use super::{Annotation, AnnotationType};
 
 #[derive(Default,Debug)]
@@ -17,6 +17,7 @@ pub struct AnnotatedString {
 }
 
 impl AnnotatedString {
+    #[must_use]
     pub fn from(string: &str) -> Self {
         Self {
             string: String::from(string),
@@ -76,7 +77,7 @@ impl AnnotatedString {
                 if shortened {
                     annotation.start.saturating_sub(len_difference)
                 } else {
-                    annotation.start.saturating_sub(len_difference)
+                    annotation.start.saturating_add(len_difference)
                 }
             } else if annotation.start >= start {
                 if shortened {
@@ -122,6 +123,11 @@ impl AnnotatedString {
              && annotation.start < self.string.len()
         });
     }    
+
+    #[must_use]
+    pub fn iter(&self) -> AnnotatedStringIterator<'_> {
+        self.into_iter()
+    }
 }
 
 impl Display for AnnotatedString {
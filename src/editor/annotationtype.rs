@@ -1,7 +1,8 @@
-#[derive(Copy,Clone,Eq,PartialEq,Debug)]
+#[derive(Copy,Clone,Eq,PartialEq,Hash,Debug)]
 pub enum AnnotationType {
     Match,
     SelectedMatch,
+    Selection,
     Number,
     Keyword,
     Type,
@@ -10,6 +11,8 @@ pub enum AnnotationType {
     LifetimeSpecifier,
     Comment,
     String,
+    Key,
+    Error,
 }
 
 
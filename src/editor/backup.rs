@@ -0,0 +1,211 @@
+use std::{
+    fs,
+    io::Error,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_BACKUP_KEEP: usize = 10;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+// "20261019T153000.123456789Z"
+const TIMESTAMP_LEN: usize = 26;
+// The longest file name most file systems allow, in bytes.
+const MAX_NAME_LEN: usize = 255;
+const MAX_PREFIX_LEN: usize = MAX_NAME_LEN - TIMESTAMP_LEN;
+// A 64-bit hash in hex.
+const HASH_LEN: usize = 16;
+
+/// What to keep of a file's previous version when saving over it.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub enum Backup {
+    #[default]
+    Off,
+    /// A copy next to the file, named like it with a `~` appended.
+    Adjacent,
+    /// Copies in `dir`, named after the file's full path and the time of
+    /// saving, of which the newest `keep` of each file are kept.
+    Directory { dir: PathBuf, keep: usize },
+}
+
+impl Backup {
+    /// Copies the file at `path` as configured, before it gets overwritten.
+    /// Does nothing if there is no file yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error saying so if the copy cannot be made. The file
+    /// should then not be overwritten.
+    pub fn back_up(&self, path: &Path) -> Result<(), Error> {
+        if !path.exists() {
+            return Ok(());
+        }
+        self.copy(path).map_err(|err| {
+            Error::new(err.kind(), format!("could not back it up ({})", err.kind()))
+        })
+    }
+
+    fn copy(&self, path: &Path) -> Result<(), Error> {
+        match self {
+            Self::Off => Ok(()),
+            Self::Adjacent => {
+                let mut backup_path = path.as_os_str().to_owned();
+                backup_path.push("~");
+                fs::copy(path, backup_path).map(|_| ())
+            }
+            Self::Directory { dir, keep } => {
+                fs::create_dir_all(dir)?;
+                let prefix = backup_prefix(path);
+                let name = format!("{prefix}{}", timestamp(SystemTime::now()));
+                fs::copy(path, dir.join(name))?;
+                prune(dir, &prefix, *keep)
+            }
+        }
+    }
+}
+
+/// The start of the backup names for `path`: its absolute path with the
+/// separators replaced by `%`, then a dot, e.g. `%home%ann%notes.txt.`.
+/// A path too long for a file name is cut down to its end, behind a hash
+/// of the whole path that keeps it apart from others ending the same way.
+fn backup_prefix(path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let flattened = path.to_string_lossy().replace(['/', '\\', ':'], "%");
+    // Leaves room for the dot.
+    if flattened.len() < MAX_PREFIX_LEN {
+        return format!("{flattened}.");
+    }
+    let max_tail_len = MAX_PREFIX_LEN.saturating_sub(HASH_LEN).saturating_sub(1);
+    let tail_start = flattened
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .find(|&idx| flattened.len().saturating_sub(idx) <= max_tail_len)
+        .unwrap_or(flattened.len());
+    let tail = flattened.get(tail_start..).unwrap_or_default();
+    format!("{:0HASH_LEN$x}{tail}.", fnv1a(flattened.as_bytes()))
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which unlike the standard library's
+/// hashers stays the same across Rust versions, so backups made by an
+/// older build are still found for pruning.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Removes all but the newest `keep` backups starting with `prefix`.
+fn prune(dir: &Path, prefix: &str, keep: usize) -> Result<(), Error> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let is_backup = name
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.len() == TIMESTAMP_LEN && rest.ends_with('Z'));
+        if is_backup {
+            names.push(name);
+        }
+    }
+    // The timestamps sort chronologically.
+    names.sort_unstable();
+    let excess = names.len().saturating_sub(keep);
+    for name in names.iter().take(excess) {
+        fs::remove_file(dir.join(name))?;
+    }
+    Ok(())
+}
+
+/// `time` in UTC, e.g. `20261019T153000.123456789Z`. The fraction keeps
+/// backups made within the same second apart.
+fn timestamp(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = duration.as_secs();
+    let (year, month, day) = civil_from_days(div(seconds, SECONDS_PER_DAY));
+    let seconds = seconds.checked_rem(SECONDS_PER_DAY).unwrap_or(0);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}.{:09}Z",
+        div(seconds, 3600),
+        div(seconds.checked_rem(3600).unwrap_or(0), 60),
+        seconds.checked_rem(60).unwrap_or(0),
+        duration.subsec_nanos()
+    )
+}
+
+/// The (year, month, day) of the given day since 1970-01-01, after Howard
+/// Hinnant's `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let shifted = days.saturating_add(719_468);
+    let era = div(shifted, 146_097);
+    let day_of_era = shifted.saturating_sub(era.saturating_mul(146_097));
+    let year_of_era = div(
+        day_of_era
+            .saturating_sub(div(day_of_era, 1460))
+            .saturating_add(div(day_of_era, 36_524))
+            .saturating_sub(div(day_of_era, 146_096)),
+        365,
+    );
+    let day_of_year = day_of_era.saturating_sub(
+        year_of_era
+            .saturating_mul(365)
+            .saturating_add(div(year_of_era, 4))
+            .saturating_sub(div(year_of_era, 100)),
+    );
+    // Months counted from March, so that February comes last.
+    let shifted_month = div(day_of_year.saturating_mul(5).saturating_add(2), 153);
+    let day = day_of_year
+        .saturating_sub(div(shifted_month.saturating_mul(153).saturating_add(2), 5))
+        .saturating_add(1);
+    let month = if shifted_month < 10 {
+        shifted_month.saturating_add(3)
+    } else {
+        shifted_month.saturating_sub(9)
+    };
+    let year = year_of_era
+        .saturating_add(era.saturating_mul(400))
+        .saturating_add(u64::from(month <= 2));
+    (year, month, day)
+}
+
+fn div(dividend: u64, divisor: u64) -> u64 {
+    dividend.checked_div(divisor).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn at(seconds: u64, nanos: u32) -> String {
+        timestamp(UNIX_EPOCH.checked_add(Duration::new(seconds, nanos)).unwrap_or(UNIX_EPOCH))
+    }
+
+    #[test]
+    fn timestamps_are_utc_and_sort_chronologically() {
+        assert_eq!(at(0, 0), "19700101T000000.000000000Z");
+        assert_eq!(at(1_792_423_800, 123_456_789), "20261019T153000.123456789Z");
+        assert_eq!(at(1_709_251_199, 0), "20240229T235959.000000000Z");
+        assert_eq!(at(951_868_800, 0), "20000301T000000.000000000Z");
+        assert_eq!(at(0, 0).len(), TIMESTAMP_LEN);
+        assert!(at(1_709_251_199, 1) < at(1_709_251_200, 0));
+    }
+
+    #[test]
+    fn backup_names_flatten_the_path() {
+        let prefix = backup_prefix(Path::new("/no/such/dir/notes.txt"));
+        assert_eq!(prefix, "%no%such%dir%notes.txt.");
+    }
+
+    #[test]
+    fn long_backup_names_are_cut_to_fit() {
+        let deep = format!("/no/such/{}/notes.txt", ["dir"; 100].join("/"));
+        let prefix = backup_prefix(Path::new(&deep));
+        assert_eq!(prefix.len().saturating_add(TIMESTAMP_LEN), MAX_NAME_LEN);
+        assert!(prefix.ends_with("%dir%dir%notes.txt."));
+        let other = backup_prefix(Path::new(&deep.replacen("such", "much", 1)));
+        assert_ne!(prefix, other);
+        assert_eq!(prefix.len(), other.len());
+        let wide = format!("/{}/notes.txt", "ü".repeat(200));
+        assert!(backup_prefix(Path::new(&wide)).len() <= MAX_PREFIX_LEN);
+    }
+}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crossterm::event::{
-    KeyCode::{Backspace, Char, Delete, Enter, Tab},
+    KeyCode::{BackTab, Backspace, Char, Delete, Enter, Tab},
     KeyEvent, KeyModifiers,
 };
 #[derive(Clone,Copy)]
@@ -8,6 +8,7 @@ pub enum Edit {
     InsertNewline,
     Delete,
     DeleteBackward,
+    Dedent,
 }
 
 impl TryFrom<KeyEvent> for Edit {
@@ -19,6 +20,7 @@ impl TryFrom<KeyEvent> for Edit {
                 Ok(Self::Insert(character))
             }
             (Tab,KeyModifiers::NONE) => Ok(Self::Insert('\t')),
+            (BackTab,KeyModifiers::NONE | KeyModifiers::SHIFT) => Ok(Self::Dedent),
             (Enter,KeyModifiers::NONE) => Ok(Self::InsertNewline),
             (Backspace,KeyModifiers::NONE) => Ok(Self::DeleteBackward),
             (Delete, KeyModifiers::NONE) => Ok(Self::Delete),
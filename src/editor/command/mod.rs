@@ -7,6 +7,8 @@ mod system;
 pub use system::System;
 mod edit;
 pub use edit::Edit;
+mod mouse;
+pub use mouse::Mouse;
 
 
 #[derive(Clone,Copy)]
@@ -14,6 +16,7 @@ pub enum Command {
     Move(Move),
     Edit(Edit),
     System(System),
+    Mouse(Mouse),
 }
 
 #[allow(clippy::as_conversions)]
@@ -31,6 +34,7 @@ impl TryFrom<Event> for Command{
                 height:height_u16 as usize,
                 width: width_u16 as usize,
             }))),
+            Event::Mouse(mouse_event) => Mouse::try_from(mouse_event).map(Command::Mouse),
             _ => Err(format!("Event not supported: {event:?}")),
         }
     }   
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+#[derive(Clone, Copy)]
+pub enum Mouse {
+    Click(Position),
+    Drag(Position),
+    ScrollUp,
+    ScrollDown,
+}
+
+impl TryFrom<MouseEvent> for Mouse {
+    type Error = String;
+
+    fn try_from(event: MouseEvent) -> Result<Self, Self::Error> {
+        let MouseEvent {
+            kind, column, row, ..
+        } = event;
+
+        #[allow(clippy::as_conversions)]
+        let position = Position {
+            col: column as usize,
+            row: row as usize,
+        };
+
+        match kind {
+            MouseEventKind::Down(MouseButton::Left) => Ok(Self::Click(position)),
+            MouseEventKind::Drag(MouseButton::Left) => Ok(Self::Drag(position)),
+            MouseEventKind::ScrollUp => Ok(Self::ScrollUp),
+            MouseEventKind::ScrollDown => Ok(Self::ScrollDown),
+            _ => Err(format!("Unsupported mouse event: {kind:?}")),
+        }
+    }
+}
\ No newline at end of file
@@ -10,7 +10,14 @@ pub enum System {
     Resize(Size),
     Quit,
     Dismiss,
-    Search
+    Search,
+    SetFileType,
+    SetTheme,
+    SetLineEnding,
+    SetEncoding,
+    ToggleReadOnly,
+    NextFile,
+    PreviousFile,
 }
 
 impl TryFrom<KeyEvent> for System {
@@ -24,6 +31,13 @@ impl TryFrom<KeyEvent> for System {
                 Char('q') => Ok(Self::Quit),
                 Char('s') => Ok(Self::Save),
                 Char('f') => Ok(Self::Search),
+                Char('t') => Ok(Self::SetFileType),
+                Char('k') => Ok(Self::SetTheme),
+                Char('e') => Ok(Self::SetLineEnding),
+                Char('o') => Ok(Self::SetEncoding),
+                Char('r') => Ok(Self::ToggleReadOnly),
+                Char('n') => Ok(Self::NextFile),
+                Char('p') => Ok(Self::PreviousFile),
                 _ => Err(format!("Unsupported CONTROL + {code:?} combination")),
             }
         } else if modifiers == KeyModifiers::NONE && matches!(code,KeyCode::Esc){
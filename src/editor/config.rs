@@ -0,0 +1,223 @@
+use std::{
+    env,
+    fs::read_to_string,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::prelude::*;
+
+use super::backup::DEFAULT_BACKUP_KEEP;
+use super::terminal::ColorDepth;
+use super::{Backup, IndentationOptions};
+
+const CONFIG_FILE_NAME: &str = "config";
+
+#[derive(Default)]
+pub struct Config {
+    pub theme: Option<String>,
+    pub color_depth: Option<ColorDepth>,
+    pub styled_underline: Option<bool>,
+    pub indentation: IndentationOptions,
+    pub backup: Option<bool>,
+    pub backup_dir: Option<PathBuf>,
+    pub backup_keep: Option<usize>,
+    pub autosave: Option<Duration>,
+    pub large_file_size: Option<u64>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, String> {
+        match config_dir() {
+            Some(dir) => Self::load_from(&dir.join(CONFIG_FILE_NAME)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        match read_to_string(path) {
+            Ok(source) => Self::parse(&source)
+                .map_err(|err| format!("{}:{err}", path.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Could not read {}: {err}", path.display())),
+        }
+    }
+
+    /// `backup = on` keeps `file~` next to each saved file, unless
+    /// `backup_dir` asks for timestamped copies in a central directory.
+    pub fn backup(&self) -> Backup {
+        match (self.backup, &self.backup_dir) {
+            (Some(false), _) | (None, None) => Backup::Off,
+            (_, Some(dir)) => Backup::Directory {
+                dir: dir.clone(),
+                keep: self.backup_keep.unwrap_or(DEFAULT_BACKUP_KEEP),
+            },
+            (Some(true), None) => Backup::Adjacent,
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let line_number = line_idx.saturating_add(1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("{line_number}: expected 'key = value'"))?;
+            let value = value.trim();
+            match key.trim() {
+                "theme" => config.theme = Some(value.to_string()),
+                "color_depth" => {
+                    config.color_depth = if value == "auto" {
+                        None
+                    } else {
+                        Some(ColorDepth::from_name(value).ok_or_else(|| {
+                            format!("{line_number}: unknown colour depth '{value}'")
+                        })?)
+                    };
+                }
+                "styled_underline" => {
+                    config.styled_underline = match value {
+                        "auto" => None,
+                        "on" | "true" => Some(true),
+                        "off" | "false" => Some(false),
+                        _ => return Err(format!("{line_number}: expected auto, on or off")),
+                    };
+                }
+                "tab_width" => config.indentation.tab_width = Some(parse_positive(value, line_number)?),
+                "indent_size" => {
+                    config.indentation.indent_size = Some(parse_positive(value, line_number)?);
+                }
+                "expand_tabs" => {
+                    config.indentation.expand_tabs = match value {
+                        "auto" => None,
+                        "on" | "true" => Some(true),
+                        "off" | "false" => Some(false),
+                        _ => return Err(format!("{line_number}: expected auto, on or off")),
+                    };
+                }
+                "backup" => {
+                    config.backup = match value {
+                        "on" | "true" => Some(true),
+                        "off" | "false" => Some(false),
+                        _ => return Err(format!("{line_number}: expected on or off")),
+                    };
+                }
+                "backup_dir" => config.backup_dir = Some(expand_home(value)),
+                "backup_keep" => config.backup_keep = Some(parse_positive(value, line_number)?),
+                "autosave" => {
+                    config.autosave = match value {
+                        "off" => None,
+                        seconds => Some(Duration::from_secs(
+                            parse_positive(seconds, line_number)?.try_into().unwrap_or(u64::MAX),
+                        )),
+                    };
+                }
+                "large_file_size" => config.large_file_size = Some(parse_size(value, line_number)?),
+                key => return Err(format!("{line_number}: unknown setting '{key}'")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse_positive(value: &str, line_number: usize) -> Result<ColIdx, String> {
+    match value.parse() {
+        Ok(width) if width > 0 => Ok(width),
+        _ => Err(format!("{line_number}: expected a positive number, found '{value}'")),
+    }
+}
+
+/// Reads a size in bytes, optionally with a `K`, `M` or `G` suffix.
+fn parse_size(value: &str, line_number: usize) -> Result<u64, String> {
+    let (number, unit) = match value.char_indices().last() {
+        Some((idx, 'K' | 'k')) => (&value[..idx], 1 << 10),
+        Some((idx, 'M' | 'm')) => (&value[..idx], 1 << 20),
+        Some((idx, 'G' | 'g')) => (&value[..idx], 1 << 30),
+        _ => (value, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .ok_or_else(|| format!("{line_number}: expected a size like 64M, found '{value}'"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    let base_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base_dir.join(NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_read() -> Result<(), String> {
+        let config = Config::parse(
+            "# comment\n\
+             theme = solarized\n\
+             color_depth = 256\n\
+             tab_width = 8\n\
+             expand_tabs = on\n\
+             backup_dir = /tmp/backups\n\
+             backup_keep = 3\n\
+             autosave = 30\n\
+             large_file_size = 2M\n",
+        )?;
+        assert_eq!(config.theme.as_deref(), Some("solarized"));
+        assert_eq!(config.color_depth, Some(ColorDepth::Ansi256));
+        assert_eq!(config.indentation.tab_width, Some(8));
+        assert_eq!(config.indentation.expand_tabs, Some(true));
+        assert_eq!(config.autosave, Some(Duration::from_secs(30)));
+        assert_eq!(config.large_file_size, Some(2 << 20));
+        assert_eq!(
+            config.backup(),
+            Backup::Directory {
+                dir: PathBuf::from("/tmp/backups"),
+                keep: 3
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn backups_are_off_unless_asked_for() -> Result<(), String> {
+        assert_eq!(Config::parse("")?.backup(), Backup::Off);
+        assert_eq!(Config::parse("backup = on")?.backup(), Backup::Adjacent);
+        assert_eq!(Config::parse("backup = off\nbackup_dir = /b")?.backup(), Backup::Off);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = |source| Config::parse(source).err();
+        assert_eq!(error("\nfoo"), Some("2: expected 'key = value'".to_string()));
+        assert_eq!(error("colour = red"), Some("1: unknown setting 'colour'".to_string()));
+        assert_eq!(
+            error("tab_width = 0"),
+            Some("1: expected a positive number, found '0'".to_string())
+        );
+        assert_eq!(
+            error("large_file_size = 2T"),
+            Some("1: expected a size like 64M, found '2T'".to_string())
+        );
+        assert_eq!(error("expand_tabs = yes"), Some("1: expected auto, on or off".to_string()));
+    }
+}
\ No newline at end of file
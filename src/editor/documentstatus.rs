@@ -1,4 +1,5 @@
 use super::filetype::FileType;
+use super::{Encoding, LineEnding};
 use crate::prelude::*;
 
 #[derive(Default,PartialEq,Eq,Debug)]
@@ -6,8 +7,17 @@ pub struct DocumentStatus {
     pub total_lines: usize,
     pub current_line_idx: LineIdx,
     pub is_modified: bool,
+    pub is_read_only: bool,
+    pub buffer_idx: usize,
+    pub buffer_count: usize,
     pub file_name: String,
     pub file_type: FileType,
+    pub line_ending: Option<LineEnding>,
+    pub encoding: Encoding,
+    pub is_binary: bool,
+    pub total_bytes: usize,
+    pub current_byte_idx: ByteIdx,
+    pub loading_progress: Option<u8>,
 }
 
 impl DocumentStatus {
@@ -19,11 +29,36 @@ impl DocumentStatus {
         }
     }
 
+    pub fn read_only_indicator_to_string(&self) -> String {
+        if self.is_read_only {
+            " [RO]".to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    pub fn buffer_indicator_to_string(&self) -> String {
+        if self.buffer_count > 1 {
+            format!(" ({}/{})", self.buffer_idx.saturating_add(1), self.buffer_count)
+        } else {
+            String::new()
+        }
+    }
+
     pub fn line_count_to_string(&self) -> String {
-        format!("{} lines", self.total_lines)
+        if self.is_binary {
+            format!("{} bytes", self.total_bytes)
+        } else if let Some(percent) = self.loading_progress {
+            format!("{} lines (indexing {percent}%)", self.total_lines)
+        } else {
+            format!("{} lines", self.total_lines)
+        }
     }
 
     pub fn position_indicator_to_string(&self) -> String {
+        if self.is_binary {
+            return format!("{:08x}/{:08x}", self.current_byte_idx, self.total_bytes);
+        }
         format!(
             "{}/{}",
             self.current_line_idx.saturating_add(1),
@@ -34,5 +69,14 @@ impl DocumentStatus {
     pub fn file_type_to_string(&self) -> String {
         self.file_type.to_string()
     }
+
+    pub fn encoding_to_string(&self) -> String {
+        self.encoding.to_string()
+    }
+
+    pub fn line_ending_to_string(&self) -> String {
+        self.line_ending
+            .map_or_else(|| "Mixed".to_string(), |line_ending| line_ending.to_string())
+    }
     
 }
\ No newline at end of file
@@ -0,0 +1,229 @@
+// EditorConfig section globs: `*` matches anything but `/`, `**` matches
+// anything, `?` a single character, `[abc]`, `[a-z]` and `[!abc]` character
+// classes, `{a,b}` alternatives and `{1..10}` integer ranges. A backslash
+// escapes the next character.
+
+enum Token {
+    Literal(char),
+    AnyChar,
+    Star,
+    DoubleStar,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Alternatives(Vec<Vec<Token>>),
+    NumberRange(i64, i64),
+}
+
+pub struct Glob {
+    tokens: Vec<Token>,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        Self {
+            tokens: parse(&chars),
+        }
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        matches(&self.tokens.iter().collect::<Vec<_>>(), &text)
+    }
+}
+
+fn parse(chars: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while let Some(&character) = chars.get(idx) {
+        idx = idx.saturating_add(1);
+        let token = match character {
+            '\\' => {
+                let escaped = chars.get(idx).copied().unwrap_or('\\');
+                idx = idx.saturating_add(1);
+                Token::Literal(escaped)
+            }
+            '?' => Token::AnyChar,
+            '*' if chars.get(idx) == Some(&'*') => {
+                idx = idx.saturating_add(1);
+                Token::DoubleStar
+            }
+            '*' => Token::Star,
+            '[' => match parse_class(&chars[idx..]) {
+                Some((token, len)) => {
+                    idx = idx.saturating_add(len);
+                    token
+                }
+                None => Token::Literal('['),
+            },
+            '{' => match parse_braces(&chars[idx..]) {
+                Some((token, len)) => {
+                    idx = idx.saturating_add(len);
+                    token
+                }
+                None => Token::Literal('{'),
+            },
+            character => Token::Literal(character),
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Parses a character class after its `[`. Returns the token and the
+/// number of characters consumed, including the closing `]`.
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let end = chars.iter().position(|&character| character == ']')?;
+    let mut body = &chars[..end];
+    let negated = matches!(body.first(), Some('!' | '^'));
+    if negated {
+        body = &body[1..];
+    }
+
+    let mut ranges = Vec::new();
+    let mut idx = 0;
+    while let Some(&start) = body.get(idx) {
+        if let (Some('-'), Some(&end)) = (body.get(idx.saturating_add(1)), body.get(idx.saturating_add(2))) {
+            ranges.push((start, end));
+            idx = idx.saturating_add(3);
+        } else {
+            ranges.push((start, start));
+            idx = idx.saturating_add(1);
+        }
+    }
+    Some((Token::Class { negated, ranges }, end.saturating_add(1)))
+}
+
+/// Parses `{...}` after its `{`. Returns the token and the number of
+/// characters consumed, including the closing `}`. Braces without a comma
+/// or a range are literal.
+fn parse_braces(chars: &[char]) -> Option<(Token, usize)> {
+    let mut depth: usize = 0;
+    let mut escaped = false;
+    let mut commas = Vec::new();
+    let mut end = None;
+    for (idx, &character) in chars.iter().enumerate() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth = depth.saturating_add(1),
+            '}' if depth == 0 => {
+                end = Some(idx);
+                break;
+            }
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => commas.push(idx),
+            _ => {}
+        }
+    }
+    let end = end?;
+    let body = &chars[..end];
+
+    if commas.is_empty() {
+        let text: String = body.iter().collect();
+        let (start, stop) = text.split_once("..")?;
+        let range = Token::NumberRange(start.parse().ok()?, stop.parse().ok()?);
+        return Some((range, end.saturating_add(1)));
+    }
+
+    let mut alternatives = Vec::new();
+    let mut start = 0;
+    for comma in commas.into_iter().chain([end]) {
+        alternatives.push(parse(&body[start..comma.min(body.len())]));
+        start = comma.saturating_add(1);
+    }
+    Some((Token::Alternatives(alternatives), end.saturating_add(1)))
+}
+
+fn matches(tokens: &[&Token], text: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return text.is_empty();
+    };
+
+    match token {
+        Token::Literal(expected) => {
+            text.first() == Some(expected) && matches(rest, &text[1..])
+        }
+        Token::AnyChar => {
+            text.first().is_some_and(|&character| character != '/') && matches(rest, &text[1..])
+        }
+        Token::Star => (0..=text.len())
+            .take_while(|&len| len == 0 || text[len.saturating_sub(1)] != '/')
+            .any(|len| matches(rest, &text[len..])),
+        Token::DoubleStar => (0..=text.len()).any(|len| matches(rest, &text[len..])),
+        Token::Class { negated, ranges } => text.first().is_some_and(|&character| {
+            let in_class = ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&character));
+            character != '/' && in_class != *negated && matches(rest, &text[1..])
+        }),
+        Token::Alternatives(alternatives) => alternatives.iter().any(|alternative| {
+            let tokens: Vec<&Token> = alternative.iter().chain(rest.iter().copied()).collect();
+            matches(&tokens, text)
+        }),
+        Token::NumberRange(start, end) => {
+            let sign = usize::from(text.first() == Some(&'-'));
+            let digits = text[sign..]
+                .iter()
+                .take_while(|character| character.is_ascii_digit())
+                .count();
+            (1..=digits).any(|len| {
+                let len = sign.saturating_add(len);
+                let number: String = text[..len].iter().collect();
+                number
+                    .parse::<i64>()
+                    .is_ok_and(|number| (*start..=*end).contains(&number))
+                    && matches(rest, &text[len..])
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Glob;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Glob::new(pattern).matches(text)
+    }
+
+    #[test]
+    fn stars() {
+        assert!(matches("*.rs", "main.rs"));
+        assert!(!matches("*.rs", "src/main.rs"));
+        assert!(matches("**.rs", "src/main.rs"));
+        assert!(matches("src/**/mod.rs", "src/editor/tests/mod.rs"));
+        assert!(matches("a?c", "abc"));
+        assert!(!matches("a?c", "a/c"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[ab].txt", "b.txt"));
+        assert!(matches("[a-c]x", "cx"));
+        assert!(!matches("[a-c]x", "dx"));
+        assert!(matches("[!a-c]x", "dx"));
+        assert!(!matches("[!a-c]x", "ax"));
+        assert!(matches("[x", "[x"));
+    }
+
+    #[test]
+    fn braces() {
+        assert!(matches("*.{js,ts}", "a.ts"));
+        assert!(!matches("*.{js,ts}", "a.rs"));
+        assert!(matches("{a,{b,c}d}", "cd"));
+        assert!(matches("file{1..10}", "file10"));
+        assert!(!matches("file{1..10}", "file11"));
+        assert!(matches("n{-5..5}", "n-3"));
+        assert!(matches("{x}", "{x}"));
+    }
+
+    #[test]
+    fn escapes() {
+        assert!(matches(r"\*.txt", "*.txt"));
+        assert!(!matches(r"\*.txt", "a.txt"));
+        assert!(matches(r"a\{b,c}", "a{b,c}"));
+    }
+}
\ No newline at end of file
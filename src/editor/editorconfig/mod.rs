@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{self, Path},
+};
+
+use crate::prelude::*;
+
+use super::{IndentationOptions, LineEnding};
+
+mod glob;
+use glob::Glob;
+
+const FILE_NAME: &str = ".editorconfig";
+
+/// The settings `.editorconfig` files give a single file. Unset fields are
+/// left to hecto's own configuration.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct EditorConfig {
+    pub indentation: IndentationOptions,
+    pub end_of_line: Option<LineEnding>,
+    pub charset: Option<String>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+impl EditorConfig {
+    /// Collects the settings for `path` from the `.editorconfig` files in
+    /// its directory and above, up to the first one marked `root = true`.
+    /// Files that cannot be read are skipped.
+    #[must_use]
+    pub fn for_path(path: &Path) -> Self {
+        let Ok(path) = path::absolute(path) else {
+            return Self::default();
+        };
+
+        let mut sources = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            if let Ok(source) = read_to_string(dir.join(FILE_NAME)) {
+                let is_root = is_root(&source);
+                sources.push((dir, source));
+                if is_root {
+                    break;
+                }
+            }
+        }
+
+        let mut properties = HashMap::new();
+        for (dir, source) in sources.iter().rev() {
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let relative = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+            apply_sections(source, &relative, &mut properties);
+        }
+        Self::from_properties(&properties)
+    }
+
+    fn from_properties(properties: &HashMap<String, String>) -> Self {
+        let get = |key: &str| properties.get(key).map(String::as_str);
+        let flag = |key: &str| match get(key) {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+        let width = |key: &str| {
+            get(key)
+                .and_then(|value| value.parse::<ColIdx>().ok())
+                .filter(|width| *width > 0)
+        };
+
+        let tab_width = width("tab_width");
+        let indent_size = if get("indent_size") == Some("tab") {
+            tab_width
+        } else {
+            width("indent_size")
+        };
+
+        Self {
+            indentation: IndentationOptions {
+                tab_width: tab_width.or(indent_size),
+                indent_size,
+                expand_tabs: match get("indent_style") {
+                    Some("space") => Some(true),
+                    Some("tab") => Some(false),
+                    _ => None,
+                },
+            },
+            end_of_line: get("end_of_line").and_then(LineEnding::from_name),
+            charset: get("charset").map(str::to_string),
+            trim_trailing_whitespace: flag("trim_trailing_whitespace"),
+            insert_final_newline: flag("insert_final_newline"),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim().to_ascii_lowercase(), value.trim().to_string()))
+}
+
+fn is_comment(line: &str) -> bool {
+    line.is_empty() || line.starts_with('#') || line.starts_with(';')
+}
+
+fn is_root(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !is_comment(line))
+        .filter_map(parse_line)
+        .any(|(key, value)| key == "root" && value.eq_ignore_ascii_case("true"))
+}
+
+/// Applies the properties of every section of `source` whose glob matches
+/// `relative`, the file's path below the `.editorconfig` file starting with
+/// a `/`. Later sections override earlier ones and `unset` removes a
+/// property.
+fn apply_sections(source: &str, relative: &str, properties: &mut HashMap<String, String>) {
+    let mut in_matching_section = false;
+    for line in source.lines().map(str::trim) {
+        if is_comment(line) {
+            continue;
+        }
+        if let Some(section) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let pattern = if section.contains('/') {
+                format!("/{}", section.trim_start_matches('/'))
+            } else {
+                format!("**/{section}")
+            };
+            in_matching_section = Glob::new(&pattern).matches(relative);
+            continue;
+        }
+        if !in_matching_section {
+            continue;
+        }
+        if let Some((key, value)) = parse_line(line) {
+            let value = value.to_ascii_lowercase();
+            if value == "unset" {
+                properties.remove(&key);
+            } else {
+                properties.insert(key, value);
+            }
+        }
+    }
+}
\ No newline at end of file
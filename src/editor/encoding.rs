@@ -0,0 +1,233 @@
+use std::char::{decode_utf16, REPLACEMENT_CHARACTER};
+use std::fmt::{self, Display};
+use std::io::{Error, ErrorKind};
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16BE_BOM: &[u8] = &[0xfe, 0xff];
+// How much of a file without a byte order mark is looked at to recognise
+// UTF-16.
+const SNIFF_LEN: usize = 4096;
+
+/// How a file's text is stored as bytes.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, the fallback for legacy single-byte files: every byte is
+    /// a character, so any file can be opened and saved back unchanged.
+    Latin1,
+}
+
+impl Encoding {
+    /// Accepts the names `.editorconfig` uses for `charset`, and a few
+    /// common spellings.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(Self::Utf8),
+            "utf-8-bom" | "utf8-bom" => Some(Self::Utf8Bom),
+            "utf-16le" | "utf16le" => Some(Self::Utf16Le),
+            "utf-16be" | "utf16be" => Some(Self::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" => Some(Self::Latin1),
+            _ => None,
+        }
+    }
+
+    /// Guesses how `bytes` are encoded. A byte order mark decides; without
+    /// one, mostly-ASCII UTF-16 shows itself through its zero bytes, valid
+    /// UTF-8 is taken as such, and anything else as Latin-1.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            Self::Utf8Bom
+        } else if bytes.starts_with(UTF16LE_BOM) {
+            Self::Utf16Le
+        } else if bytes.starts_with(UTF16BE_BOM) {
+            Self::Utf16Be
+        } else if let Some(utf16) = guess_utf16(bytes) {
+            utf16
+        } else if std::str::from_utf8(bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Latin1
+        }
+    }
+
+    /// Like `detect`, for the start of a longer file: a character cut off at
+    /// the end does not count against UTF-8.
+    #[must_use]
+    pub fn detect_prefix(bytes: &[u8]) -> Self {
+        let complete = match std::str::from_utf8(bytes) {
+            Err(err) if err.error_len().is_none() => {
+                bytes.get(..err.valid_up_to()).unwrap_or(bytes)
+            }
+            _ => bytes,
+        };
+        Self::detect(complete)
+    }
+
+    /// The text in `bytes`, without the byte order mark. Sequences that are
+    /// invalid in this encoding become U+FFFD.
+    #[must_use]
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 | Self::Utf8Bom => {
+                String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)).into_owned()
+            }
+            Self::Utf16Le => decode_utf16_bytes(
+                bytes.strip_prefix(UTF16LE_BOM).unwrap_or(bytes),
+                u16::from_le_bytes,
+            ),
+            Self::Utf16Be => decode_utf16_bytes(
+                bytes.strip_prefix(UTF16BE_BOM).unwrap_or(bytes),
+                u16::from_be_bytes,
+            ),
+            Self::Latin1 => bytes.iter().copied().map(char::from).collect(),
+        }
+    }
+
+    /// `text` as bytes in this encoding, with the byte order mark if the
+    /// encoding has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidData` error naming the first character the encoding
+    /// cannot represent.
+    pub fn encode(self, text: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Utf8 => Ok(text.as_bytes().to_vec()),
+            Self::Utf8Bom => Ok([UTF8_BOM, text.as_bytes()].concat()),
+            Self::Utf16Le => Ok(UTF16LE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect()),
+            Self::Utf16Be => Ok(UTF16BE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+                .collect()),
+            Self::Latin1 => text
+                .chars()
+                .map(|character| {
+                    u8::try_from(character).map_err(|_| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            format!("'{character}' cannot be saved as {self}"),
+                        )
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8 => write!(formatter, "UTF-8"),
+            Self::Utf8Bom => write!(formatter, "UTF-8 BOM"),
+            Self::Utf16Le => write!(formatter, "UTF-16LE"),
+            Self::Utf16Be => write!(formatter, "UTF-16BE"),
+            Self::Latin1 => write!(formatter, "Latin-1"),
+        }
+    }
+}
+
+/// Recognises UTF-16 without a byte order mark by the zero high byte of its
+/// ASCII characters: more than half the code units have one, always on the
+/// same side.
+fn guess_utf16(bytes: &[u8]) -> Option<Encoding> {
+    let units = bytes.chunks_exact(2);
+    if bytes.is_empty() || !units.remainder().is_empty() {
+        return None;
+    }
+    let sample: Vec<&[u8]> = units.take(SNIFF_LEN).collect();
+    let zero_high = sample.iter().filter(|unit| unit[0] != 0 && unit[1] == 0).count();
+    let zero_low = sample.iter().filter(|unit| unit[0] == 0 && unit[1] != 0).count();
+    if zero_high.saturating_mul(2) > sample.len() && zero_low == 0 {
+        Some(Encoding::Utf16Le)
+    } else if zero_low.saturating_mul(2) > sample.len() && zero_high == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16_bytes(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2);
+    let has_odd_byte = !units.remainder().is_empty();
+    let mut text: String = decode_utf16(units.map(|unit| to_unit([unit[0], unit[1]])))
+        .map(|character| character.unwrap_or(REPLACEMENT_CHARACTER))
+        .collect();
+    if has_odd_byte {
+        text.push(REPLACEMENT_CHARACTER);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection() {
+        assert_eq!(Encoding::detect(b"plain"), Encoding::Utf8);
+        assert_eq!(Encoding::detect("Grüße".as_bytes()), Encoding::Utf8);
+        assert_eq!(Encoding::detect(b"\xef\xbb\xbfa"), Encoding::Utf8Bom);
+        assert_eq!(Encoding::detect(b"\xff\xfea\0"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(b"\xfe\xff\0a"), Encoding::Utf16Be);
+        assert_eq!(Encoding::detect(b"a\0b\0c\0"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(b"\0a\0b\0c"), Encoding::Utf16Be);
+        assert_eq!(Encoding::detect(b"Gr\xfc\xdfe"), Encoding::Latin1);
+        assert_eq!(Encoding::detect(b""), Encoding::Utf8);
+    }
+
+    #[test]
+    fn prefixes_may_end_in_the_middle_of_a_character() {
+        let cut = &"Grüße".as_bytes()[..3];
+        assert_eq!(Encoding::detect(cut), Encoding::Latin1);
+        assert_eq!(Encoding::detect_prefix(cut), Encoding::Utf8);
+        assert_eq!(Encoding::detect_prefix(b"Gr\xfc\xdfe"), Encoding::Latin1);
+    }
+
+    #[test]
+    fn text_survives_a_round_trip() -> Result<(), Error> {
+        let text = "Grüße, 世界 🦀\n";
+        for encoding in [Encoding::Utf8, Encoding::Utf8Bom, Encoding::Utf16Le, Encoding::Utf16Be] {
+            let bytes = encoding.encode(text)?;
+            assert_eq!(Encoding::detect(&bytes), encoding);
+            assert_eq!(encoding.decode(&bytes), text);
+        }
+        let bytes = Encoding::Latin1.encode("Grüße")?;
+        assert_eq!(bytes, b"Gr\xfc\xdfe");
+        assert_eq!(Encoding::Latin1.decode(&bytes), "Grüße");
+        Ok(())
+    }
+
+    #[test]
+    fn unrepresentable_characters_are_refused() {
+        let err = Encoding::Latin1.encode("a€").map(|_| ()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "'€' cannot be saved as Latin-1");
+    }
+
+    #[test]
+    fn invalid_sequences_are_replaced() {
+        assert_eq!(Encoding::Utf8.decode(b"a\xffb"), "a\u{fffd}b");
+        assert_eq!(Encoding::Utf16Le.decode(b"a\0\0\xd8b"), "a\u{fffd}\u{fffd}");
+        assert_eq!(Encoding::Utf16Le.decode(b"a\0b"), "a\u{fffd}");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Encoding::from_name("UTF_16LE"), Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::from_name("utf-8-bom"), Some(Encoding::Utf8Bom));
+        assert_eq!(Encoding::from_name("iso-8859-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("koi8-r"), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+use super::FileType;
+
+/// How `Editor::open_with` opens a file.
+#[derive(Clone, Copy, Default)]
+pub struct FileOptions {
+    /// Where to put the caret.
+    pub at: Location,
+    pub read_only: bool,
+    /// Overrides the detected file type.
+    pub file_type: Option<FileType>,
+}
\ No newline at end of file
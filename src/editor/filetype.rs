@@ -1,16 +1,176 @@
-use std::{default, fmt::{write, Display},};
+use std::{fmt::Display, path::Path};
+
+const MODELINE_SCAN_LINES: usize = 5;
 
 #[derive(Default,PartialEq, Eq,Debug,Clone, Copy)]
 pub enum FileType {
     Rust,
+    Json,
+    Toml,
+    Yaml,
+    Python,
+    Shell,
+    Makefile,
+    Dockerfile,
     #[default]
     Text,
 }
 
+impl FileType {
+    #[must_use]
+    pub fn detect(path: Option<&Path>, contents: &str) -> Self {
+        let lines: Vec<&str> = contents.lines().collect();
+        let head = lines.iter().take(MODELINE_SCAN_LINES);
+        let tail = lines
+            .iter()
+            .skip(MODELINE_SCAN_LINES)
+            .rev()
+            .take(MODELINE_SCAN_LINES);
+
+        head.chain(tail)
+            .find_map(|line| Self::from_modeline(line))
+            .or_else(|| path.and_then(Self::from_path))
+            .or_else(|| lines.first().and_then(|line| Self::from_shebang(line)))
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name().and_then(|name| name.to_str())?;
+        match file_name {
+            "Makefile" | "makefile" | "GNUmakefile" => return Some(Self::Makefile),
+            "Dockerfile" | "Containerfile" => return Some(Self::Dockerfile),
+            "Cargo.lock" | "Pipfile" => return Some(Self::Toml),
+            ".bashrc" | ".bash_profile" | ".profile" | ".zshrc" => return Some(Self::Shell),
+            _ => {}
+        }
+        if file_name.starts_with("Dockerfile.") {
+            return Some(Self::Dockerfile);
+        }
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| match ext.to_ascii_lowercase().as_str() {
+                "rs" => Some(Self::Rust),
+                "json" => Some(Self::Json),
+                "toml" => Some(Self::Toml),
+                "yaml" | "yml" => Some(Self::Yaml),
+                "py" | "pyw" => Some(Self::Python),
+                "sh" | "bash" | "zsh" => Some(Self::Shell),
+                "mk" => Some(Self::Makefile),
+                "txt" => Some(Self::Text),
+                _ => None,
+            })
+    }
+
+    #[must_use]
+    pub fn from_shebang(first_line: &str) -> Option<Self> {
+        let command = first_line.strip_prefix("#!")?.trim();
+        let mut words = command.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|word| !word.starts_with('-'))?;
+        }
+        let program = program.trim_end_matches(|char: char| char.is_ascii_digit() || char == '.');
+        match program {
+            "python" => Some(Self::Python),
+            "sh" | "bash" | "zsh" | "dash" | "ksh" => Some(Self::Shell),
+            "make" => Some(Self::Makefile),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn from_modeline(line: &str) -> Option<Self> {
+        Self::from_emacs_modeline(line).or_else(|| Self::from_vim_modeline(line))
+    }
+
+    fn from_emacs_modeline(line: &str) -> Option<Self> {
+        let start = line.find("-*-")?.saturating_add(3);
+        let length = line[start..].find("-*-")?;
+        let settings = line[start..start.saturating_add(length)].trim();
+        if !settings.contains(':') {
+            return Self::from_name(settings);
+        }
+        settings.split(';').find_map(|setting| {
+            let (key, value) = setting.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("mode")
+                .then(|| Self::from_name(value.trim()))
+                .flatten()
+        })
+    }
+
+    fn from_vim_modeline(line: &str) -> Option<Self> {
+        let options = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+            line.match_indices(marker)
+                .find(|(idx, _)| *idx == 0 || line[..*idx].ends_with(char::is_whitespace))
+                .map(|(idx, _)| &line[idx.saturating_add(marker.len())..])
+        })?;
+        let options = options.trim_start();
+        let options = options
+            .strip_prefix("set ")
+            .or_else(|| options.strip_prefix("se "))
+            .map_or(options, |options| options.split(':').next().unwrap_or_default());
+
+        options
+            .split(|char: char| char == ':' || char.is_whitespace())
+            .find_map(|option| {
+                let (key, value) = option.split_once('=')?;
+                matches!(key, "ft" | "filetype" | "syntax" | "syn")
+                    .then(|| Self::from_name(value))
+                    .flatten()
+            })
+    }
+
+    #[must_use]
+    pub const fn opens_block(self, character: char) -> bool {
+        match self {
+            Self::Rust | Self::Json | Self::Toml | Self::Shell => {
+                matches!(character, '{' | '(' | '[')
+            }
+            Self::Python | Self::Yaml => matches!(character, '{' | '(' | '[' | ':'),
+            Self::Makefile | Self::Dockerfile | Self::Text => false,
+        }
+    }
+
+    #[must_use]
+    pub const fn closes_block(self, character: char) -> bool {
+        match self {
+            Self::Rust | Self::Json | Self::Toml | Self::Shell | Self::Python | Self::Yaml => {
+                matches!(character, '}' | ')' | ']')
+            }
+            Self::Makefile | Self::Dockerfile | Self::Text => false,
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::Rust),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "python" | "py" => Some(Self::Python),
+            "shell" | "sh" | "bash" | "zsh" | "shell-script" => Some(Self::Shell),
+            "make" | "makefile" => Some(Self::Makefile),
+            "dockerfile" | "docker" => Some(Self::Dockerfile),
+            "text" | "txt" | "plain" | "fundamental" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
 impl Display for FileType {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Rust => write!(formatter, "Rust"),
+            Self::Json => write!(formatter, "JSON"),
+            Self::Toml => write!(formatter, "TOML"),
+            Self::Yaml => write!(formatter, "YAML"),
+            Self::Python => write!(formatter, "Python"),
+            Self::Shell => write!(formatter, "Shell"),
+            Self::Makefile => write!(formatter, "Makefile"),
+            Self::Dockerfile => write!(formatter, "Dockerfile"),
             Self::Text => write!(formatter, "Text"),
         }
     }
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+use super::FileType;
+
+pub const DEFAULT_TAB_WIDTH: ColIdx = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Indentation {
+    pub tab_width: ColIdx,
+    pub indent_size: ColIdx,
+    pub expand_tabs: bool,
+}
+
+impl Indentation {
+    /// Tab inserts a tab unless configured otherwise; the file type only
+    /// decides how many spaces a level takes once tabs are expanded.
+    #[must_use]
+    pub const fn for_file_type(file_type: FileType) -> Self {
+        let indent_size = match file_type {
+            FileType::Json | FileType::Yaml => 2,
+            FileType::Rust
+            | FileType::Toml
+            | FileType::Python
+            | FileType::Shell
+            | FileType::Makefile
+            | FileType::Dockerfile
+            | FileType::Text => DEFAULT_TAB_WIDTH,
+        };
+        Self {
+            tab_width: DEFAULT_TAB_WIDTH,
+            indent_size,
+            expand_tabs: false,
+        }
+    }
+
+    /// The whitespace that makes up one level of indentation.
+    #[must_use]
+    pub fn unit(&self) -> String {
+        if self.expand_tabs {
+            " ".repeat(self.indent_size)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// Removes one level from the end of `whitespace`: a tab, or up to
+    /// `indent_size` spaces.
+    #[must_use]
+    pub fn dedent<'a>(&self, whitespace: &'a str) -> &'a str {
+        if let Some(kept) = whitespace.strip_suffix('\t') {
+            return kept;
+        }
+        let spaces = whitespace
+            .chars()
+            .rev()
+            .take(self.indent_size)
+            .take_while(|character| *character == ' ')
+            .count();
+        &whitespace[..whitespace.len().saturating_sub(spaces)]
+    }
+}
+
+/// Indentation settings that override the file type's defaults; unset
+/// fields fall back to them.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct IndentationOptions {
+    pub tab_width: Option<ColIdx>,
+    pub indent_size: Option<ColIdx>,
+    pub expand_tabs: Option<bool>,
+}
+
+impl IndentationOptions {
+    /// Fills the fields unset here from `fallback`.
+    #[must_use]
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            tab_width: self.tab_width.or(fallback.tab_width),
+            indent_size: self.indent_size.or(fallback.indent_size),
+            expand_tabs: self.expand_tabs.or(fallback.expand_tabs),
+        }
+    }
+
+    #[must_use]
+    pub fn resolve(&self, file_type: FileType) -> Indentation {
+        let defaults = Indentation::for_file_type(file_type);
+        Indentation {
+            tab_width: self.tab_width.unwrap_or(defaults.tab_width),
+            indent_size: self.indent_size.unwrap_or(defaults.indent_size),
+            expand_tabs: self.expand_tabs.unwrap_or(defaults.expand_tabs),
+        }
+    }
+}
\ No newline at end of file
@@ -19,6 +19,7 @@ pub struct Line {
 }
 
 impl Line {
+    #[must_use]
     pub fn from(line_str: &str) -> Self {
         debug_assert!(line_str.is_empty() || line_str.lines().count() == 1);
         let fragments = Self::str_to_fragments(line_str);
@@ -61,31 +62,30 @@ impl Line {
 
     fn get_replacement_character(for_str: &str) -> Option<char> {
         let width = for_str.width();
+        let mut chars = for_str.chars();
+        let is_control = chars.next().is_some_and(char::is_control) && chars.next().is_none();
         match for_str {
             " " => None,
             "\t" => Some(' '),
+            _ if is_control => Some('▯'),
             _ if width > 0 && for_str.trim().is_empty() => Some('␣'),
-            _ if width == 0 => {
-                let mut chars = for_str.chars();
-                if let Some(ch) = chars.next() {
-                    if ch.is_control() && chars.next().is_none() {
-                        return Some('▯');
-                    }
-                }
-                Some('·')
-            }
+            _ if width == 0 => Some('·'),
             _ => None,
         }
     }
 
-    pub fn get_visible_graphemes(&self, range: Range<ColIdx>) -> String {
-        self.get_annotated_visible_substr(range, None).to_string()
+    #[must_use]
+    pub fn get_visible_graphemes(&self, range: Range<ColIdx>, tab_width: ColIdx) -> String {
+        self.get_annotated_visible_substr(range, None, tab_width)
+            .to_string()
     }
 
+    #[must_use]
     pub fn get_annotated_visible_substr(
         &self,
         range: Range<ColIdx>,
-        annotations: Option<&Vec<Annotation>>
+        annotations: Option<&Vec<Annotation>>,
+        tab_width: ColIdx,
     ) -> AnnotatedString {
         if range.start >= range.end {
             return AnnotatedString::default();
@@ -99,17 +99,24 @@ impl Line {
             }
         }
 
-        let mut fragment_start = self.width();
-        for fragment in self.fragments.iter().rev() {
-            let fragment_end = fragment_start;
-            fragment_start = fragment_start.saturating_sub(fragment.rendered_width.into());
-
+        let columns = self.fragment_columns(tab_width);
+        for (fragment, &(fragment_start, fragment_end)) in
+            self.fragments.iter().zip(columns.iter()).rev()
+        {
             if fragment_start > range.end {
                 continue;
             }
 
+            // Tabs are drawn as spaces, so a partially visible tab shows
+            // as many of them as fit instead of an ellipsis.
+            let is_tab = fragment.grapheme == "\t";
             if fragment_start < range.end && fragment_end > range.end {
-                result.replace(fragment.start, self.string.len(), "⋯");
+                let clipped = if is_tab {
+                    " ".repeat(range.end.saturating_sub(fragment_start))
+                } else {
+                    "⋯".to_string()
+                };
+                result.replace(fragment.start, self.string.len(), &clipped);
                 continue;
             } else if fragment_start == range.end {
                 result.truncate_right_from(fragment.start);
@@ -120,41 +127,75 @@ impl Line {
                 result.truncate_left_until(fragment.start.saturating_add(fragment.grapheme.len()));
                 break;
             } else if fragment_start < range.start && fragment_end > range.start {
+                let clipped = if is_tab {
+                    " ".repeat(fragment_end.saturating_sub(range.start))
+                } else {
+                    "⋯".to_string()
+                };
                 result.replace(
                     0,
                     fragment.start.saturating_add(fragment.grapheme.len()),
-                    "⋯",
+                    &clipped,
                 );
                 break;
             }
             if fragment_start >= range.start && fragment_end <= range.end {
-                if let Some(replacement) = fragment.replacement {
+                let replacement = if is_tab {
+                    Some(" ".repeat(fragment_end.saturating_sub(fragment_start)))
+                } else {
+                    fragment.replacement.map(String::from)
+                };
+                if let Some(replacement) = replacement {
                     let start = fragment.start;
                     let end = start.saturating_add(fragment.grapheme.len());
-                    result.replace(start, end, &replacement.to_string());
+                    result.replace(start, end, &replacement);
                 }
             }
         }
         result
     }
 
+    #[must_use]
     pub fn grapheme_count(&self) -> GraphemeIdx {
         self.fragments.len()
     }
 
-    pub fn width_until(&self, grapheme_idx: GraphemeIdx) -> ColIdx {
+    #[must_use]
+    pub fn width_until(&self, grapheme_idx: GraphemeIdx, tab_width: ColIdx) -> ColIdx {
         self.fragments
             .iter()
             .take(grapheme_idx)
-            .map(|fragment| match fragment.rendered_width {
-                GraphemeWidth::Half => 1,
-                GraphemeWidth::Full => 2,
+            .fold(0, |col, fragment| {
+                col.saturating_add(Self::fragment_width(fragment, col, tab_width))
             })
-            .sum()
     }
 
-    pub fn width(&self) -> ColIdx {
-        self.width_until(self.grapheme_count())
+    #[must_use]
+    pub fn width(&self, tab_width: ColIdx) -> ColIdx {
+        self.width_until(self.grapheme_count(), tab_width)
+    }
+
+    /// The rendered width of `fragment` when it starts at column `col`:
+    /// a tab reaches up to the next multiple of `tab_width`.
+    fn fragment_width(fragment: &TextFragment, col: ColIdx, tab_width: ColIdx) -> ColIdx {
+        if fragment.grapheme == "\t" {
+            let tab_width = tab_width.max(1);
+            tab_width.saturating_sub(col.checked_rem(tab_width).unwrap_or(0))
+        } else {
+            fragment.rendered_width.into()
+        }
+    }
+
+    fn fragment_columns(&self, tab_width: ColIdx) -> Vec<(ColIdx, ColIdx)> {
+        let mut col: ColIdx = 0;
+        self.fragments
+            .iter()
+            .map(|fragment| {
+                let start = col;
+                col = col.saturating_add(Self::fragment_width(fragment, col, tab_width));
+                (start, col)
+            })
+            .collect()
     }
 
     pub fn insert_char(&mut self, character: char, at: GraphemeIdx) {
@@ -190,6 +231,7 @@ impl Line {
         self.rebuild_fragments();
     }
 
+    #[must_use]
     pub fn split(&mut self, at: GraphemeIdx) -> Self {
         if let Some(fragment) = self.fragments.get(at) {
             let remainder = self.string.split_off(fragment.start);
@@ -208,28 +250,48 @@ impl Line {
             .iter()
             .position(|fragment| fragment.start >= byte_idx)
     }
-    fn grapheme_idx_to_byte_idx(&self, grapheme_idx: GraphemeIdx) -> ByteIdx {
+    #[must_use]
+    pub fn grapheme_idx_to_byte_idx(&self, grapheme_idx: GraphemeIdx) -> ByteIdx {
         debug_assert!(grapheme_idx <= self.grapheme_count());
+        self.fragments
+            .get(grapheme_idx)
+            .map_or(self.string.len(), |fragment| fragment.start)
+    }
 
-        if grapheme_idx == 0 || self.grapheme_count() == 0 {
-            return 0;
-        }
+    #[must_use]
+    pub fn grapheme_idx_at(&self, col: ColIdx, tab_width: ColIdx) -> GraphemeIdx {
+        self.fragment_columns(tab_width)
+            .iter()
+            .position(|&(_, end)| col < end)
+            .unwrap_or_else(|| self.grapheme_count())
+    }
 
-        self.fragments.get(grapheme_idx).map_or_else(
-            || {
-                #[cfg(debug_assertions)]
-                {
-                    panic!("Fragment not found for grapheme index: {grapheme_idx:?}");
-                }
-                #[cfg(not(debug_assertions))]
-                {
-                    0
-                }
-            },
-            |fragment| fragment.start,
-        )
+    #[must_use]
+    pub fn word_bounds(&self, at: GraphemeIdx) -> Range<GraphemeIdx> {
+        let class_of = |fragment: &TextFragment| {
+            let mut chars = fragment.grapheme.chars();
+            match chars.next() {
+                Some(character) if character.is_alphanumeric() || character == '_' => 0,
+                Some(character) if character.is_whitespace() => 1,
+                _ => 2,
+            }
+        };
+        let Some(class) = self.fragments.get(at).map(class_of) else {
+            return at..at;
+        };
+
+        let start = self.fragments[..at]
+            .iter()
+            .rposition(|fragment| class_of(fragment) != class)
+            .map_or(0, |idx| idx.saturating_add(1));
+        let end = self.fragments[at..]
+            .iter()
+            .position(|fragment| class_of(fragment) != class)
+            .map_or(self.grapheme_count(), |idx| idx.saturating_add(at));
+        start..end
     }
 
+    #[must_use]
     pub fn search_forward(
         &self,
         query: &str,
@@ -248,6 +310,7 @@ impl Line {
             .map(|(_, grapheme_idx)| *grapheme_idx)
     }
 
+    #[must_use]
     pub fn search_backward(
         &self,
         query: &str,
@@ -0,0 +1,151 @@
+use std::fmt::{self, Display};
+
+// Beyond this many cells the longest-common-subsequence table gets too big,
+// and the changed region is shown as removed wholesale, then added.
+const MAX_TABLE_CELLS: usize = 1 << 22;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiffLine<'a> {
+    Unchanged(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+impl Display for DiffLine<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unchanged(line) => write!(formatter, " {line}"),
+            Self::Removed(line) => write!(formatter, "-{line}"),
+            Self::Added(line) => write!(formatter, "+{line}"),
+        }
+    }
+}
+
+/// The lines that turn `old` into `new`, in order.
+#[must_use]
+pub fn diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old_middle = &old[prefix..old.len().saturating_sub(suffix)];
+    let new_middle = &new[prefix..new.len().saturating_sub(suffix)];
+
+    let mut result: Vec<_> = old[..prefix].iter().copied().map(DiffLine::Unchanged).collect();
+    result.extend(diff_middle(old_middle, new_middle));
+    result.extend(
+        old[old.len().saturating_sub(suffix)..]
+            .iter()
+            .copied()
+            .map(DiffLine::Unchanged),
+    );
+    result
+}
+
+fn diff_middle<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let width = new.len().saturating_add(1);
+    let cells = old.len().saturating_add(1).saturating_mul(width);
+    if cells > MAX_TABLE_CELLS {
+        return old
+            .iter()
+            .copied()
+            .map(DiffLine::Removed)
+            .chain(new.iter().copied().map(DiffLine::Added))
+            .collect();
+    }
+
+    // lcs[i * width + j] is the length of the longest common subsequence of
+    // old[i..] and new[j..].
+    let mut lcs = vec![0_usize; cells];
+    for old_idx in (0..old.len()).rev() {
+        for new_idx in (0..new.len()).rev() {
+            let here = old_idx.saturating_mul(width).saturating_add(new_idx);
+            let below = here.saturating_add(width);
+            lcs[here] = if old[old_idx] == new[new_idx] {
+                lcs[below.saturating_add(1)].saturating_add(1)
+            } else {
+                lcs[below].max(lcs[here.saturating_add(1)])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut old_idx, mut new_idx) = (0, 0);
+    while old_idx < old.len() && new_idx < new.len() {
+        let here = old_idx.saturating_mul(width).saturating_add(new_idx);
+        if old[old_idx] == new[new_idx] {
+            result.push(DiffLine::Unchanged(old[old_idx]));
+            old_idx = old_idx.saturating_add(1);
+            new_idx = new_idx.saturating_add(1);
+        } else if lcs[here.saturating_add(width)] >= lcs[here.saturating_add(1)] {
+            result.push(DiffLine::Removed(old[old_idx]));
+            old_idx = old_idx.saturating_add(1);
+        } else {
+            result.push(DiffLine::Added(new[new_idx]));
+            new_idx = new_idx.saturating_add(1);
+        }
+    }
+    result.extend(old[old_idx..].iter().copied().map(DiffLine::Removed));
+    result.extend(new[new_idx..].iter().copied().map(DiffLine::Added));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_lines_are_kept() {
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "x", "c", "d", "e"];
+        assert_eq!(
+            diff(&old, &new),
+            [
+                DiffLine::Unchanged("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Unchanged("c"),
+                DiffLine::Unchanged("d"),
+                DiffLine::Added("e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn moved_lines_are_removed_and_added() {
+        let old = ["a", "b", "c"];
+        let new = ["c", "a", "b"];
+        assert_eq!(
+            diff(&old, &new),
+            [
+                DiffLine::Added("c"),
+                DiffLine::Unchanged("a"),
+                DiffLine::Unchanged("b"),
+                DiffLine::Removed("c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_sides() {
+        assert_eq!(diff(&[], &["a"]), [DiffLine::Added("a")]);
+        assert_eq!(diff(&["a"], &[]), [DiffLine::Removed("a")]);
+        assert!(diff(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn lines_are_shown_with_their_marker() {
+        let lines: Vec<String> = diff(&["a", "b"], &["a", "c"])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, [" a", "-b", "+c"]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use std::fmt::{self, Display};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "lf" => Some(Self::Lf),
+            "crlf" => Some(Self::CrLf),
+            "cr" => Some(Self::Cr),
+            _ => None,
+        }
+    }
+
+    /// Splits `contents` at LF, CRLF and CR line breaks. Returns each line
+    /// with the break that ends it, and whether the last line has one.
+    #[must_use]
+    pub fn split(contents: &str) -> (Vec<(&str, Self)>, bool) {
+        let mut lines = Vec::new();
+        let mut rest = contents;
+        while let Some(idx) = rest.find(['\n', '\r']) {
+            let (line_ending, len) = if rest[idx..].starts_with("\r\n") {
+                (Self::CrLf, 2)
+            } else if rest[idx..].starts_with('\r') {
+                (Self::Cr, 1)
+            } else {
+                (Self::Lf, 1)
+            };
+            lines.push((&rest[..idx], line_ending));
+            rest = &rest[idx.saturating_add(len)..];
+        }
+
+        let has_final_newline = rest.is_empty();
+        if !has_final_newline {
+            // Should the last line ever get a break, it uses the one before.
+            let line_ending = lines.last().map_or_else(Self::default, |(_, previous)| *previous);
+            lines.push((rest, line_ending));
+        }
+        (lines, has_final_newline)
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+            Self::Cr => "\r",
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lf => write!(formatter, "LF"),
+            Self::CrLf => write!(formatter, "CRLF"),
+            Self::Cr => write!(formatter, "CR"),
+        }
+    }
+}
\ No newline at end of file
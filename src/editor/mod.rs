@@ -1,7 +1,7 @@
 use crate::prelude::*;
-use crossterm::event::{read, Event, KeyEvent, KeyEventKind};
+use crossterm::event::{poll, read, Event, KeyEvent, KeyEventKind};
 use std::{
-    env,io::Error, panic::{set_hook, take_hook}
+    io::{Error, Write}, mem, panic::{set_hook, take_hook}, path::Path, time::{Duration, Instant}
 };
 
 mod annotatedstring;
@@ -13,29 +13,68 @@ mod uicomponents;
 mod terminal;
 pub use annotationtype::AnnotationType;
 mod annotation;
-use annotation::Annotation;
+mod backup;
+pub use annotation::Annotation;
+use backup::Backup;
 mod filetype;
-use annotatedstring::AnnotatedString;
+mod fileoptions;
+mod indentation;
+mod editorconfig;
+mod encoding;
+mod lineending;
+mod linediff;
+mod config;
+mod theme;
+#[cfg(test)]
+mod tests;
+pub use annotatedstring::{AnnotatedString, AnnotatedStringPart};
 use uicomponents::{CommandBar,MessageBar,View, StatusBar, UIComponent};
+pub use uicomponents::{create_syntax_highlight, Buffer, SyntaxHighlighter};
 
 use documentstatus::DocumentStatus;
-use filetype::FileType;
-use line::Line;
-use terminal::Terminal;
+pub use filetype::FileType;
+pub use fileoptions::FileOptions;
+use indentation::{Indentation, IndentationOptions};
+use editorconfig::EditorConfig;
+pub use encoding::Encoding;
+pub use lineending::LineEnding;
+pub use line::Line;
+use config::Config;
+use terminal::{detect_styled_underline, Backend, ColorDepth, CrosstermBackend, Terminal};
+use theme::{Theme, ThemeElement};
 
 use self::command::{
-    Command::{self, Edit, Move, System},
-    Edit::InsertNewline,
+    Command::{self, Edit, Mouse, Move, System},
+    Edit::{Insert, InsertNewline},
+    Mouse::{Click, Drag, ScrollDown, ScrollUp},
     Move::{Down,Left,Right,Up},
-    System::{Dismiss, Quit, Resize, Save,Search},
+    System::{
+        Dismiss, NextFile, PreviousFile, Quit, Resize, Save, Search, SetEncoding, SetFileType,
+        SetLineEnding, SetTheme, ToggleReadOnly,
+    },
 };
 
 const QUIT_TIMES: u8 = 3;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLL_LINES: usize = 3;
+const SWAP_INTERVAL: Duration = Duration::from_secs(4);
+const SWAP_EDITS: usize = 200;
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const READ_ONLY_MESSAGE: &str = "File is read-only. Press Ctrl-R to allow editing.";
+const LARGE_FILE_MESSAGE: &str = "Large file: without syntax highlighting, editable once indexed.";
+const INDEXING_MESSAGE: &str = "Large file is still being indexed.";
 
 #[derive(PartialEq, Eq,Default)]
 enum PromptType {
     Search,
     Save,
+    FileType,
+    Theme,
+    LineEnding,
+    Encoding,
+    ExternalChange,
+    Recover,
     #[default]
     None,
 }
@@ -57,42 +96,136 @@ pub struct Editor {
     terminal_size: Size,
     title: String,
     quit_times: u8,
+    shown_diagnostic: Option<String>,
+    last_click: Option<(Instant, Position)>,
+    mouse_selecting: bool,
+    swap_written_at: Option<Instant>,
+    edits_since_swap: usize,
+    autosave_after: Option<Duration>,
+    last_input: Option<Instant>,
+    disk_checked_at: Option<Instant>,
+    // The indexing progress of a large file last shown in the status bar.
+    loading_progress: Option<u8>,
+    pipe_mode: bool,
 }
 
 impl Editor {
+    /// Starts an editor on the real terminal, using the user's configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be put into raw mode.
     pub fn new() -> Result<Self, Error> {
+        Self::with_config_file(None)
+    }
+
+    /// Like `new`, reading the configuration from `config_file` instead of
+    /// the user's configuration directory if given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be put into raw mode.
+    pub fn with_config_file(config_file: Option<&Path>) -> Result<Self, Error> {
         let current_hook = take_hook();
         set_hook(Box::new(move |panic_info| {
             let _ = Terminal::terminate();
             current_hook(panic_info);
         }));
+
+        let mut editor = Self::with_backend(CrosstermBackend::default())?;
+
+        let config = config_file.map_or_else(Config::load, Config::load_from);
+        let config = config.unwrap_or_else(|err| {
+            editor.update_message(&format!("ERR: {err}"));
+            Config::default()
+        });
+        editor.apply_config(&config);
+
+        Ok(editor)
+    }
+
+    /// Starts an editor drawing through `backend`, with the default settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend fails to initialize.
+    pub(crate) fn with_backend(backend: impl Backend + 'static) -> Result<Self, Error> {
+        Terminal::set_backend(backend);
         Terminal::initialize()?;
 
         let mut editor = Self::default();
         let size = Terminal::size().unwrap_or_default();
         editor.handle_resize_command(size);
-        editor.update_message("HELP: Ctrl - F = find | Ctrl - S = save | Ctrl - Q = quit");
+        editor.update_message(
+            "HELP: Ctrl - F = find | Ctrl - S = save | Ctrl - T = file type | Ctrl - K = theme | Ctrl - E = line endings | Ctrl - O = encoding | Ctrl - R = read-only | Ctrl - Q = quit",
+        );
+        editor.refresh_status();
+        Ok(editor)
+    }
 
-        let args: Vec<String> = env::args().collect();
-        if let Some(file_name) = args.get(1) {
-            debug_assert!(!file_name.is_empty());
-            if editor.view.load(file_name).is_err() {
-                editor.update_message(&format!("ERR: Could not open file: {file_name}"));
-            }
+    pub fn open(&mut self, file_name: &str) {
+        self.open_with(file_name, FileOptions::default());
+    }
+
+    /// Opens `file_name`, behind the files already open if there are any.
+    pub fn open_with(&mut self, file_name: &str, options: FileOptions) {
+        if self.view.load(file_name, options).is_err() {
+            self.update_message(&format!("ERR: Could not open file: {file_name}"));
+        } else if self.view.buffer_count() == 1 && self.view.has_swap_file() {
+            self.set_prompt(PromptType::Recover);
+        } else if self.view.is_large_file() {
+            self.update_message(LARGE_FILE_MESSAGE);
         }
+        self.refresh_status();
+    }
 
-        editor.refresh_status();
-        Ok(editor)
+    /// Opens `bytes` that do not come from a file, such as piped input, like
+    /// `open_with`.
+    pub fn open_bytes(&mut self, bytes: Vec<u8>, options: FileOptions) {
+        self.view.load_bytes(bytes, options);
+        self.refresh_status();
+    }
+
+    /// In pipe mode the current document is the editor's output, so quitting
+    /// does not warn about its unsaved changes. Write it with `write_text`.
+    pub fn set_pipe_mode(&mut self, enabled: bool) {
+        self.pipe_mode = enabled;
     }
 
+    /// Ends the editor, restoring the terminal, then writes the text of the
+    /// current document to `out`. A large file is streamed from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text cannot be read or written.
+    pub fn write_text(mut self, out: &mut impl Write) -> Result<(), Error> {
+        let view = mem::take(&mut self.view);
+        drop(self);
+        view.write_text(out)
+    }
+
+    /// Reads and handles terminal events until the user quits, doing idle
+    /// work whenever no event arrives for `IDLE_POLL_INTERVAL`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if an event cannot be read from the terminal.
     pub fn run(&mut self) {
         loop {
             self.refresh_screen();
-            if self.should_quit {
+            if self.should_quit() {
                 break;
             }
-            match read() {
-                Ok(event) => self.evaluate_event(event),
+            let event = match poll(IDLE_POLL_INTERVAL) {
+                Ok(true) => read(),
+                Ok(false) => {
+                    self.handle_idle();
+                    continue;
+                }
+                Err(err) => Err(err),
+            };
+            match event {
+                Ok(event) => self.handle_event(event),
                 Err(err) => {
                     #[cfg(debug_assertions)]
                     {
@@ -104,10 +237,77 @@ impl Editor {
                     }
                 }
             }
-            self.refresh_screen();
         }
     }
 
+    pub fn handle_event(&mut self, event: Event) {
+        self.last_input = Some(Instant::now());
+        self.evaluate_event(event);
+        self.update_swap_file();
+        self.refresh_status();
+        self.refresh_screen();
+    }
+
+    /// Catches up on work put off while the user was typing: writing the
+    /// swap file, autosaving and looking for changes on disk. Expired
+    /// messages are cleared by the next refresh.
+    fn handle_idle(&mut self) {
+        // A large file grows while its lines are indexed, and is drawn once
+        // more when indexing is done.
+        if self.loading_progress.is_some() {
+            self.view.set_needs_redraw(true);
+            self.refresh_status();
+        }
+        self.update_swap_file();
+        let check_disk = self
+            .disk_checked_at
+            .is_none_or(|checked_at| checked_at.elapsed() >= DISK_CHECK_INTERVAL);
+        if check_disk {
+            self.disk_checked_at = Some(Instant::now());
+            self.pick_up_external_change();
+            self.refresh_status();
+        }
+        let idle_long_enough = self.autosave_after.is_some_and(|autosave_after| {
+            self.last_input
+                .is_none_or(|last_input| last_input.elapsed() >= autosave_after)
+        });
+        if idle_long_enough {
+            self.autosave();
+        }
+    }
+
+    /// Saves a modified, file-backed buffer if autosave is on, unless that
+    /// would overwrite someone else's changes or interrupt a prompt.
+    fn autosave(&mut self) {
+        if self.autosave_after.is_none()
+            || self.in_prompt()
+            || !self.view.is_file_loaded()
+            || self.view.is_read_only()
+            || !self.view.get_status().is_modified
+        {
+            return;
+        }
+        // Retry a failed autosave only after another idle period.
+        self.last_input = Some(Instant::now());
+        if self.view.changed_on_disk() {
+            self.update_message("Autosave skipped: file changed on disk.");
+            return;
+        }
+        match self.view.save() {
+            Ok(()) => {
+                self.reset_swap_file();
+                self.update_message("File autosaved.");
+            }
+            Err(err) => self.update_message(&format!("Error autosaving file: {}.", err.kind())),
+        }
+        self.refresh_status();
+    }
+
+    #[must_use]
+    pub const fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
     fn refresh_screen(&mut self) {
         if self.terminal_size.height == 0 || self.terminal_size.width == 0 {
             return
@@ -117,6 +317,12 @@ impl Editor {
 
         let _ = Terminal::hide_caret();
 
+        if self.terminal_size.height > 2 {
+            self.view.render(0);
+        }
+
+        self.show_diagnostic();
+
         if self.in_prompt(){
             self.command_bar.render(bottom_bar_row);
         } else {
@@ -127,30 +333,39 @@ impl Editor {
             self.status_bar.render(self.terminal_size.height.saturating_sub(2));
         }
 
-        if self.terminal_size.height > 2 {
-            self.view.render(0);
-        }
-
         let new_caret_pos = if self.in_prompt() {
-            Position {
-                col: bottom_bar_row,
-                row: self.command_bar.caret_position_col(),
-            }
+            Some(Position {
+                row: bottom_bar_row,
+                col: self.command_bar.caret_position_col(),
+            })
         } else {
             self.view.caret_position()
         };
 
-        debug_assert!(new_caret_pos.col <= self.terminal_size.width);
-        debug_assert!(new_caret_pos.row <= self.terminal_size.height);
+        if let Some(new_caret_pos) = new_caret_pos {
+            debug_assert!(new_caret_pos.col <= self.terminal_size.width);
+            debug_assert!(new_caret_pos.row <= self.terminal_size.height);
 
-        let _ = Terminal::move_caret_to(new_caret_pos);
-        let _ = Terminal::show_caret();
+            let _ = Terminal::move_caret_to(new_caret_pos);
+            let _ = Terminal::show_caret();
+        }
         let _ = Terminal::execute();
 
     }
 
+    fn show_diagnostic(&mut self) {
+        let diagnostic = self.view.get_diagnostic().map(str::to_string);
+        if diagnostic != self.shown_diagnostic {
+            if let Some(message) = &diagnostic {
+                self.update_message(message);
+            }
+            self.shown_diagnostic = diagnostic;
+        }
+    }
+
     fn refresh_status(&mut self){
         let status = self.view.get_status();
+        self.loading_progress = status.loading_progress;
         let title = format!("{} - {NAME}",status.file_name);
         self.status_bar.update_status(status);
         if title != self.title && matches!(Terminal::set_title(&title),Ok(())) {
@@ -160,11 +375,20 @@ impl Editor {
     }
 
     fn evaluate_event(&mut self,event: Event){
+        if event == Event::FocusGained {
+            self.pick_up_external_change();
+            return;
+        }
+        if event == Event::FocusLost {
+            self.autosave();
+            return;
+        }
+
         let should_process = match &event {
             Event::Key(KeyEvent{kind,..}) => {
                 kind == &KeyEventKind::Press
             }
-            Event::Resize(_,_) => true,
+            Event::Resize(_,_) | Event::Mouse(_) => true,
             _ => false,
         };
 
@@ -184,6 +408,12 @@ impl Editor {
         match self.prompt_type {
             PromptType::Search => self.process_command_during_search(command),
             PromptType::Save => self.process_command_during_save(command),
+            PromptType::FileType => self.process_command_during_file_type(command),
+            PromptType::Theme => self.process_command_during_theme(command),
+            PromptType::LineEnding => self.process_command_during_line_ending(command),
+            PromptType::Encoding => self.process_command_during_encoding(command),
+            PromptType::ExternalChange => self.process_command_during_external_change(command),
+            PromptType::Recover => self.process_command_during_recover(command),
             PromptType::None => self.process_command_no_prompt(command),
         }
     }
@@ -202,17 +432,125 @@ impl Editor {
 
             System(Search) => self.set_prompt(PromptType::Search),
 
+            System(SetFileType) => self.set_prompt(PromptType::FileType),
+
+            System(SetTheme) => self.set_prompt(PromptType::Theme),
+
+            System(SetLineEnding | SetEncoding) if self.view.is_binary() => {
+                self.update_message("Binary files have no line endings or encoding.");
+            }
+
+            System(SetLineEnding | SetEncoding) if self.view.is_large_file() => {
+                self.update_message("Large files keep their line endings and encoding.");
+            }
+
+            System(SetLineEnding | SetEncoding) if self.view.is_read_only() => {
+                self.update_message(READ_ONLY_MESSAGE);
+            }
+
+            System(SetLineEnding) => self.set_prompt(PromptType::LineEnding),
+
+            System(SetEncoding) => self.set_prompt(PromptType::Encoding),
+
+            System(NextFile) => self.switch_file(true),
+
+            System(PreviousFile) => self.switch_file(false),
+
+            System(ToggleReadOnly) => {
+                let read_only = !self.view.is_read_only();
+                self.view.set_read_only(read_only);
+                self.update_message(if read_only {
+                    "Read-only mode on."
+                } else {
+                    "Read-only mode off."
+                });
+            }
+
             System(Save) => self.handle_save_command(),
 
-            Edit(edit_command) => self.view.handle_edit_command(edit_command),
+            Edit(_) if self.view.loading_progress().is_some() => {
+                self.update_message(INDEXING_MESSAGE);
+            }
+
+            Edit(_) if self.view.is_read_only() => self.update_message(READ_ONLY_MESSAGE),
+
+            Edit(edit_command) => {
+                self.view.handle_edit_command(edit_command);
+                self.edits_since_swap = self.edits_since_swap.saturating_add(1);
+            }
 
             Move(move_command) => self.view.handle_move_command(move_command),
 
+            Mouse(mouse_command) => self.handle_mouse_command(mouse_command),
+
+        }
+    }
+
+    /// Keeps the swap file close behind unsaved edits: it is written as soon
+    /// as the buffer is first modified, then after `SWAP_EDITS` more edits or
+    /// `SWAP_INTERVAL`, whichever comes first.
+    fn update_swap_file(&mut self) {
+        if self.edits_since_swap == 0 || !self.view.get_status().is_modified {
+            return;
+        }
+        let due = self.edits_since_swap >= SWAP_EDITS
+            || self
+                .swap_written_at
+                .is_none_or(|written_at| written_at.elapsed() >= SWAP_INTERVAL);
+        if due {
+            if let Err(err) = self.view.write_swap_file() {
+                self.update_message(&format!("Error writing swap file: {}.", err.kind()));
+            }
+            self.swap_written_at = Some(Instant::now());
+            self.edits_since_swap = 0;
+        }
+    }
+
+    /// Lets the next edit write the swap file straight away, once the swap
+    /// file was removed.
+    fn reset_swap_file(&mut self) {
+        self.swap_written_at = None;
+        self.edits_since_swap = 0;
+    }
+
+    fn handle_mouse_command(&mut self, mouse_command: command::Mouse) {
+        match mouse_command {
+            Click(position) => {
+                self.mouse_selecting = self.is_in_view(position);
+                if !self.mouse_selecting {
+                    self.last_click = None;
+                    return;
+                }
+
+                let now = Instant::now();
+                let is_double_click = self.last_click.is_some_and(|(time, last_position)| {
+                    last_position == position && now.duration_since(time) < DOUBLE_CLICK_INTERVAL
+                });
+                if is_double_click {
+                    self.view.select_word_at(position);
+                    self.last_click = None;
+                } else {
+                    self.view.click(position);
+                    self.last_click = Some((now, position));
+                }
+            }
+            Drag(position) => {
+                if self.mouse_selecting {
+                    self.view.drag_to(position);
+                }
+            }
+            ScrollUp => self.view.scroll_up(SCROLL_LINES),
+            ScrollDown => self.view.scroll_down(SCROLL_LINES),
         }
     }
 
+    fn is_in_view(&self, position: Position) -> bool {
+        position.row < self.terminal_size.height.saturating_sub(2)
+    }
+
     fn handle_resize_command(&mut self, size: Size) {
         self.terminal_size = size;
+        Terminal::resize(size);
 
         self.view.resize(Size{
             height: size.height.saturating_sub(2),
@@ -229,17 +567,42 @@ impl Editor {
         self.command_bar.resize(bar_size);
     }
 
-    #[allow(clippy::arithmetic_side_effects)]
+    /// Moves on to the next or previous open file. The swap file of the file
+    /// left behind is brought up to date, as it gets no edits until it is
+    /// current again.
+    fn switch_file(&mut self, forward: bool) {
+        if self.view.buffer_count() == 1 {
+            self.update_message("No other files are open.");
+            return;
+        }
+        if self.view.get_status().is_modified && self.edits_since_swap > 0 {
+            if let Err(err) = self.view.write_swap_file() {
+                self.update_message(&format!("Error writing swap file: {}.", err.kind()));
+            }
+        }
+        self.view.switch_buffer(forward);
+        self.reset_swap_file();
+        if !self.view.get_status().is_modified && self.view.has_swap_file() {
+            self.set_prompt(PromptType::Recover);
+        }
+    }
+
     fn handle_quit_command(&mut self) {
-        if !self.view.get_status().is_modified || self.quit_times + 1 == QUIT_TIMES {
+        let unsaved_changes = if self.pipe_mode {
+            self.view.has_unsaved_changes_in_background()
+        } else {
+            self.view.has_unsaved_changes()
+        };
+        if !unsaved_changes || self.quit_times.saturating_add(1) == QUIT_TIMES {
+            self.view.remove_swap_files();
             self.should_quit = true;
-        } else if self.view.get_status().is_modified {
+        } else {
             self.update_message(&format!(
                 "WARNING! File has unsaved changes. Press Ctrl-Q {} more times to quit.",
-                QUIT_TIMES - self.quit_times - 1
+                QUIT_TIMES.saturating_sub(self.quit_times).saturating_sub(1)
             ));
 
-            self.quit_times += 1;
+            self.quit_times = self.quit_times.saturating_add(1);
         }
     }
 
@@ -251,7 +614,13 @@ impl Editor {
     }
 
     fn handle_save_command(&mut self){
-        if self.view.is_file_loaded(){
+        // Saving needs to know where every line is.
+        if self.view.loading_progress().is_some() {
+            self.update_message(INDEXING_MESSAGE);
+            return;
+        }
+        // A read-only file can still be saved under another name.
+        if self.view.is_file_loaded() && !self.view.is_read_only() {
             self.save(None);
         } else {
             self.set_prompt(PromptType::Save);
@@ -260,7 +629,7 @@ impl Editor {
 
     fn process_command_during_save(&mut self,command: Command) {
         match command {
-            System( Quit | Resize(_) | Search| Save) | Move(_) => {},
+            System( Quit | Resize(_) | Search| Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile) | Move(_) | Mouse(_) => {},
             
             System(Dismiss) => {
                 self.set_prompt(PromptType::None);
@@ -277,17 +646,280 @@ impl Editor {
         }
     }
 
+    fn process_command_during_file_type(&mut self, command: Command) {
+        match command {
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile) | Move(_) | Mouse(_) => {}
+
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("File type unchanged.");
+            }
+
+            Edit(InsertNewline) => {
+                let name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                if let Some(file_type) = FileType::from_name(name.trim()) {
+                    self.view.set_file_type(file_type);
+                    self.update_message(&format!("File type set to {file_type}."));
+                } else {
+                    self.update_message(&format!("Unknown file type: {name}"));
+                }
+            }
+
+            Edit(edit_command) => self.command_bar.handle_edit_command(edit_command),
+        }
+    }
+
+    fn process_command_during_theme(&mut self, command: Command) {
+        match command {
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile) | Move(_) | Mouse(_) => {}
+
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("Theme unchanged.");
+            }
+
+            Edit(InsertNewline) => {
+                let name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                match Theme::load(name.trim()) {
+                    Ok(theme) => {
+                        let message = format!("Theme set to {}.", theme.name());
+                        self.set_theme(theme);
+                        self.update_message(&message);
+                    }
+                    Err(err) => self.update_message(&format!("ERR: {err}")),
+                }
+            }
+
+            Edit(edit_command) => self.command_bar.handle_edit_command(edit_command),
+        }
+    }
+
+    fn process_command_during_line_ending(&mut self, command: Command) {
+        match command {
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile)
+            | Move(_)
+            | Mouse(_) => {}
+
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("Line endings unchanged.");
+            }
+
+            Edit(InsertNewline) => {
+                let name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                if let Some(line_ending) = LineEnding::from_name(name.trim()) {
+                    self.view.set_line_ending(line_ending);
+                    self.update_message(&format!("Line endings set to {line_ending}."));
+                } else {
+                    self.update_message(&format!("Unknown line ending: {name}"));
+                }
+            }
+
+            Edit(edit_command) => self.command_bar.handle_edit_command(edit_command),
+        }
+    }
+
+    fn process_command_during_encoding(&mut self, command: Command) {
+        match command {
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile)
+            | Move(_)
+            | Mouse(_) => {}
+
+            System(Dismiss) => {
+                self.set_prompt(PromptType::None);
+                self.update_message("Encoding unchanged.");
+            }
+
+            Edit(InsertNewline) => {
+                let name = self.command_bar.value();
+                self.set_prompt(PromptType::None);
+                if let Some(encoding) = Encoding::from_name(name.trim()) {
+                    self.view.set_encoding(encoding);
+                    self.update_message(&format!("Encoding set to {encoding}."));
+                } else {
+                    self.update_message(&format!("Unknown encoding: {name}"));
+                }
+            }
+
+            Edit(edit_command) => self.command_bar.handle_edit_command(edit_command),
+        }
+    }
+
+    fn process_command_during_external_change(&mut self, command: Command) {
+        match command {
+            // Lets the user look around the diff.
+            Move(move_command) => self.view.handle_move_command(move_command),
+
+            Mouse(mouse_command) => self.handle_mouse_command(mouse_command),
+
+            System(Dismiss) => {
+                self.view.hide_diff();
+                self.view.dismiss_disk_change();
+                self.set_prompt(PromptType::None);
+                self.update_message("File on disk left unchanged.");
+            }
+
+            Edit(Insert('r')) => {
+                self.set_prompt(PromptType::None);
+                match self.view.reload() {
+                    Ok(()) => {
+                        self.reset_swap_file();
+                        self.update_message("File reloaded from disk.");
+                    }
+                    Err(err) => {
+                        self.update_message(&format!("Error reloading file: {}.", err.kind()));
+                    }
+                }
+            }
+
+            Edit(Insert('o')) => {
+                self.view.hide_diff();
+                self.set_prompt(PromptType::None);
+                self.write_file(None);
+            }
+
+            Edit(Insert('d')) => {
+                if let Err(err) = self.view.show_disk_diff() {
+                    self.set_prompt(PromptType::None);
+                    self.update_message(&format!("Error reading file: {}.", err.kind()));
+                }
+            }
+
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile)
+            | Edit(_) => {}
+        }
+    }
+
+    fn process_command_during_recover(&mut self, command: Command) {
+        match command {
+            // Lets the user look around the diff.
+            Move(move_command) => self.view.handle_move_command(move_command),
+
+            Mouse(mouse_command) => self.handle_mouse_command(mouse_command),
+
+            System(Dismiss) => {
+                self.view.hide_diff();
+                self.view.keep_swap_file();
+                self.set_prompt(PromptType::None);
+                self.update_message("Swap file kept for later.");
+            }
+
+            // Quitting must not throw away what the crash left behind.
+            System(Quit) => {
+                self.view.hide_diff();
+                self.view.keep_swap_file();
+                self.set_prompt(PromptType::None);
+                self.handle_quit_command();
+            }
+
+            Edit(Insert('r')) => {
+                self.set_prompt(PromptType::None);
+                match self.view.recover_from_swap_file() {
+                    Ok(()) => {
+                        // The swap file already holds what was recovered.
+                        self.swap_written_at = Some(Instant::now());
+                        self.update_message("Unsaved changes recovered. Save to keep them.");
+                    }
+                    Err(err) => {
+                        self.update_message(&format!("Error reading swap file: {}.", err.kind()));
+                    }
+                }
+            }
+
+            Edit(Insert('d')) => {
+                if let Err(err) = self.view.show_swap_diff() {
+                    self.update_message(&format!("Error reading swap file: {}.", err.kind()));
+                }
+            }
+
+            Edit(Insert('x')) => {
+                self.view.hide_diff();
+                self.view.remove_swap_file();
+                self.set_prompt(PromptType::None);
+                self.update_message("Swap file discarded.");
+            }
+
+            System(Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile)
+            | Edit(_) => {}
+        }
+    }
+
+    /// Picks up changes other programs made to the file, checked when focus
+    /// returns and every `DISK_CHECK_INTERVAL` while idle: silently if there
+    /// is nothing to lose, otherwise by asking what to do. A change the user
+    /// already dismissed is not asked about again.
+    fn pick_up_external_change(&mut self) {
+        if self.in_prompt() || !self.view.has_unseen_disk_change() {
+            return;
+        }
+        if self.view.get_status().is_modified {
+            self.set_prompt(PromptType::ExternalChange);
+        } else {
+            match self.view.reload() {
+                Ok(()) => self.reset_swap_file(),
+                Err(err) => {
+                    self.update_message(&format!("Error reloading file: {}.", err.kind()));
+                }
+            }
+        }
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.view.set_indentation_options(config.indentation);
+        self.view.set_backup(config.backup());
+        self.autosave_after = config.autosave;
+        if let Some(size) = config.large_file_size {
+            self.view.set_large_file_size(size);
+        }
+
+        let color_depth = config.color_depth.unwrap_or_else(ColorDepth::detect);
+        Terminal::set_color_depth(color_depth);
+        Terminal::set_styled_underline(config.styled_underline.unwrap_or_else(|| {
+            color_depth != ColorDepth::Ansi16 && detect_styled_underline()
+        }));
+
+        if let Some(name) = &config.theme {
+            match Theme::load(name) {
+                Ok(theme) => self.set_theme(theme),
+                Err(err) => self.update_message(&format!("ERR: {err}")),
+            }
+        }
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        Terminal::set_theme(theme);
+        self.handle_resize_command(self.terminal_size);
+    }
+
     fn save(&mut self,file_name:Option<&str>){
+        if file_name.is_none() && self.view.changed_on_disk() {
+            self.set_prompt(PromptType::ExternalChange);
+            return;
+        }
+        self.write_file(file_name);
+    }
+
+    fn write_file(&mut self, file_name: Option<&str>) {
         let result = if let Some(name) = file_name {
             self.view.save_as(name)
         } else {
             self.view.save()
         };
 
-        if result.is_ok() {
-            self.update_message("File saved successfully.");
-        } else {
-            self.update_message("Error writing file!");
+        match result {
+            Ok(()) => {
+                self.reset_swap_file();
+                self.update_message("File saved successfully.");
+            }
+            // Names the character the file's encoding cannot hold, or says
+            // that the backup failed.
+            Err(err) if err.get_ref().is_some() => {
+                self.update_message(&format!("Error writing file: {err}."));
+            }
+            Err(err) => self.update_message(&format!("Error writing file: {}.", err.kind())),
         }
     }
 
@@ -311,7 +943,7 @@ impl Editor {
             }
             Move(Right | Down) => self.view.search_next(),
             Move(Up | Left) => self.view.search_prev(),
-            System(Quit | Resize(_) | Search | Save ) | Move(_) => {}
+            System(Quit | Resize(_) | Search | Save | SetFileType | SetTheme | SetLineEnding | SetEncoding | ToggleReadOnly | NextFile | PreviousFile) | Move(_) | Mouse(_) => {}
         }
     }
 
@@ -327,6 +959,21 @@ impl Editor {
         match prompt_type {
             PromptType::None => self.message_bar.set_needs_redraw(true),
             PromptType::Save => self.command_bar.set_prompt("Save as:"),
+            PromptType::FileType => self.command_bar.set_prompt("Set file type (e.g. rust, toml, python):"),
+            PromptType::LineEnding => self.command_bar.set_prompt("Line endings (lf, crlf, cr):"),
+            PromptType::Encoding => self.command_bar.set_prompt(
+                "Encoding (utf-8, utf-8-bom, utf-16le, utf-16be, latin1):",
+            ),
+            PromptType::Recover => self.command_bar.set_prompt(
+                "Unsaved changes from a crash! r = recover, d = diff, x = discard, Esc = keep",
+            ),
+            PromptType::ExternalChange => self.command_bar.set_prompt(
+                "File changed on disk! r = reload, o = overwrite, d = diff, Esc = cancel",
+            ),
+            PromptType::Theme => self.command_bar.set_prompt(&format!(
+                "Theme ({} or a file):",
+                Theme::bundled_names().join(", ")
+            )),
             PromptType::Search => {
                 self.view.enter_search();
                 self.command_bar.set_prompt("Search (Esc to cancel,Arrows to navigate):");
@@ -1,53 +1,32 @@
 use crossterm::style::Color;
-use super::super::AnnotationType;
+
+use super::Underline;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Attribute {
     pub foreground: Option<Color>,
     pub background: Option<Color>,
+    pub underline: Option<Underline>,
+    pub underline_color: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub dim: bool,
+    pub reverse: bool,
 }
 
-impl From<AnnotationType> for Attribute {
-    fn from(annotation_type: AnnotationType) -> Self {
-        match annotation_type {
-            AnnotationType::Match => Self {
-                foreground: Some(Color::Rgb { r: 255, g: 255, b: 255 }),
-                background: Some(Color::Rgb { r: 211, g: 211, b: 211 }),
-            },
-            AnnotationType::SelectedMatch => Self {
-                foreground: Some(Color::Rgb { r: 255, g: 255, b: 255 }),
-                background: Some(Color::Rgb { r: 255, g: 255, b: 153 }),
-            },
-            AnnotationType::Number => Self {
-                foreground: Some(Color::Rgb { r: 255, g: 99, b: 71 }),
-                background: None
-            },
-            AnnotationType::Keyword => Self {
-                foreground: Some(Color::Rgb { r: 100, g: 149, b: 237 }),
-                background: None
-            },
-            AnnotationType::Type => Self {
-                foreground: Some(Color::Rgb { r: 175, g: 225, b: 175 }),
-                background: None,
-            },
-            AnnotationType::KnownValue => Self {
-                foreground: Some(Color::Rgb { r: 195, g: 177, b: 225 }),
-                background: None,
-            },
-            AnnotationType::Char => Self {
-                foreground: Some(Color::Rgb { r: 255, g: 191, b: 0 }),
-                background: None,
-            },
-            AnnotationType::LifetimeSpecifier => Self {
-                foreground: Some(Color::Rgb { r: 102, g: 205, b: 170 }),
-                background: None,
-            },
-            AnnotationType::Comment => Self {
-                foreground: Some(Color::Rgb { r: 34, g: 139, b: 34 }),
-                background: None,
-            },
-            AnnotationType::String => Self {
-                foreground: Some(Color::Rgb { r: 255, g: 179, b: 102 }),
-                background: None
-            },
+impl Attribute {
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            underline: other.underline.or(self.underline),
+            underline_color: other.underline_color.or(self.underline_color),
+            bold: self.bold || other.bold,
+            italic: self.italic || other.italic,
+            dim: self.dim || other.dim,
+            reverse: self.reverse || other.reverse,
         }
     }
 }
\ No newline at end of file
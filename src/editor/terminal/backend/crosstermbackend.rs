@@ -0,0 +1,232 @@
+use std::fs::{File, OpenOptions};
+use std::io::{stdout, BufWriter, Error, IsTerminal, Stdout, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+};
+use crossterm::style::{
+    Attribute::{
+        Bold, Dim, DoubleUnderlined, Italic, Reset, Reverse, Undercurled, Underdashed,
+        Underdotted, Underlined,
+    },
+    Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor, SetUnderlineColor,
+};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, size, Clear, ClearType, DisableLineWrap, EnableLineWrap,
+    EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+};
+use crossterm::{queue, Command};
+
+use crate::prelude::*;
+
+use super::super::{Attribute, ColorDepth, Frame, ScreenCell, Underline};
+use super::Backend;
+
+#[derive(Default)]
+pub struct CrosstermBackend {
+    color_depth: ColorDepth,
+    styled_underline: bool,
+    output: Output,
+}
+
+/// Where the screen is drawn: standard output, or the controlling terminal
+/// when standard output is piped elsewhere.
+enum Output {
+    Stdout(Stdout),
+    Tty(BufWriter<File>),
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::Stdout(stdout())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::Tty(tty) => tty.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Tty(tty) => tty.flush(),
+        }
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn initialize(&mut self) -> Result<(), Error> {
+        if !stdout().is_terminal() {
+            if let Ok(tty) = OpenOptions::new().write(true).open("/dev/tty") {
+                self.output = Output::Tty(BufWriter::new(tty));
+            }
+        }
+        enable_raw_mode()?;
+        self.queue_command(EnterAlternateScreen)?;
+        self.queue_command(DisableLineWrap)?;
+        self.queue_command(EnableMouseCapture)?;
+        self.queue_command(EnableFocusChange)?;
+        self.queue_command(Clear(ClearType::All))?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn terminate(&mut self) -> Result<(), Error> {
+        self.queue_command(DisableFocusChange)?;
+        self.queue_command(DisableMouseCapture)?;
+        self.queue_command(LeaveAlternateScreen)?;
+        self.queue_command(EnableLineWrap)?;
+        self.queue_command(Show)?;
+        self.output.flush()?;
+        disable_raw_mode()?;
+        Ok(())
+    }
+
+    fn size(&self) -> Result<Size, Error> {
+        let (width_u16, height_u16) = size()?;
+
+        #[allow(clippy::as_conversions)]
+        let height = height_u16 as usize;
+
+        #[allow(clippy::as_conversions)]
+        let width = width_u16 as usize;
+        Ok(Size { height, width })
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        self.queue_command(SetTitle(title))?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn draw(
+        &mut self,
+        frame: &Frame,
+        displayed: Option<&Frame>,
+        caret: Option<Position>,
+    ) -> Result<(), Error> {
+        let mut cursor: Option<Position> = None;
+        let mut current_attribute: Option<Attribute> = None;
+
+        self.queue_command(Hide)?;
+        for (position, cell) in frame.changes(displayed) {
+            if cursor != Some(position) {
+                self.queue_caret_move(position)?;
+            }
+            if current_attribute != Some(cell.attribute) {
+                self.queue_command(SetAttribute(Reset))?;
+                self.set_attribute(&cell.attribute)?;
+                current_attribute = Some(cell.attribute);
+            }
+            self.queue_command(Print(&cell.symbol))?;
+
+            let Position { row, col } = position;
+            let next = Position {
+                row,
+                col: col.saturating_add(1),
+            };
+            let cell_width = if frame.get(next).is_some_and(ScreenCell::is_continuation) {
+                2
+            } else {
+                1
+            };
+            cursor = Some(Position {
+                row,
+                col: col.saturating_add(cell_width),
+            });
+        }
+        self.queue_command(SetAttribute(Reset))?;
+
+        if let Some(caret) = caret {
+            self.queue_caret_move(caret)?;
+            self.queue_command(Show)?;
+        }
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> Result<(), Error> {
+        self.queue_command(Print(text))?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        self.color_depth = color_depth;
+    }
+
+    fn set_styled_underline(&mut self, enabled: bool) {
+        self.styled_underline = enabled;
+    }
+}
+
+impl CrosstermBackend {
+    fn set_attribute(&mut self, attribute: &Attribute) -> Result<(), Error> {
+        if let Some(foreground_color) = attribute.foreground {
+            self.queue_command(SetForegroundColor(self.color_depth.downsample(foreground_color)))?;
+        }
+
+        if let Some(background_color) = attribute.background {
+            self.queue_command(SetBackgroundColor(self.color_depth.downsample(background_color)))?;
+        }
+
+        if attribute.bold {
+            self.queue_command(SetAttribute(Bold))?;
+        }
+
+        if attribute.dim {
+            self.queue_command(SetAttribute(Dim))?;
+        }
+
+        if attribute.italic {
+            self.queue_command(SetAttribute(Italic))?;
+        }
+
+        if attribute.reverse {
+            self.queue_command(SetAttribute(Reverse))?;
+        }
+
+        if let Some(underline) = attribute.underline {
+            self.set_underline(underline, attribute.underline_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_underline(&mut self, underline: Underline, color: Option<Color>) -> Result<(), Error> {
+        if !self.styled_underline {
+            self.queue_command(SetAttribute(Underlined))?;
+            return Ok(());
+        }
+
+        let style = match underline {
+            Underline::Straight => Underlined,
+            Underline::Double => DoubleUnderlined,
+            Underline::Curly => Undercurled,
+            Underline::Dotted => Underdotted,
+            Underline::Dashed => Underdashed,
+        };
+        self.queue_command(SetAttribute(style))?;
+
+        if let Some(color) = color {
+            self.queue_command(SetUnderlineColor(self.color_depth.downsample(color)))?;
+        }
+        Ok(())
+    }
+
+    fn queue_caret_move(&mut self, position: Position) -> Result<(), Error> {
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        self.queue_command(MoveTo(position.col as u16, position.row as u16))?;
+        Ok(())
+    }
+
+    fn queue_command<T: Command>(&mut self, command: T) -> Result<(), Error> {
+        queue!(self.output, command)?;
+        Ok(())
+    }
+}
\ No newline at end of file
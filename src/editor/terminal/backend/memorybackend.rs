@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::io::Error;
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+use super::super::{Frame, ScreenCell};
+use super::Backend;
+
+#[derive(Default)]
+struct Screen {
+    size: Size,
+    frame: Frame,
+    caret: Option<Position>,
+    title: String,
+    repainted: Vec<Position>,
+}
+
+// Renders into memory instead of a terminal. Clones share the same screen,
+// so a test can keep one handle while the editor draws through another.
+#[derive(Clone, Default)]
+pub struct MemoryBackend {
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl MemoryBackend {
+    #[must_use]
+    pub fn new(size: Size) -> Self {
+        let backend = Self::default();
+        backend.set_size(size);
+        backend
+    }
+
+    // Changes the size reported to the editor. The editor only picks it up
+    // once it receives the matching resize event.
+    pub fn set_size(&self, size: Size) {
+        self.screen.borrow_mut().size = size;
+    }
+
+    #[must_use]
+    pub fn cell(&self, position: Position) -> Option<ScreenCell> {
+        self.screen.borrow().frame.get(position).cloned()
+    }
+
+    #[must_use]
+    pub fn row_text(&self, row: RowIdx) -> String {
+        let screen = self.screen.borrow();
+        (0..screen.frame.size().width)
+            .filter_map(|col| screen.frame.get(Position { col, row }))
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    #[must_use]
+    pub fn text(&self) -> Vec<String> {
+        let height = self.screen.borrow().frame.size().height;
+        (0..height).map(|row| self.row_text(row)).collect()
+    }
+
+    #[must_use]
+    pub fn caret(&self) -> Option<Position> {
+        self.screen.borrow().caret
+    }
+
+    /// The cells the last draw had to paint, as a terminal would.
+    #[must_use]
+    pub fn repainted(&self) -> Vec<Position> {
+        self.screen.borrow().repainted.clone()
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.screen.borrow().title.clone()
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn initialize(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn terminate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn size(&self) -> Result<Size, Error> {
+        Ok(self.screen.borrow().size)
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        title.clone_into(&mut self.screen.borrow_mut().title);
+        Ok(())
+    }
+
+    fn draw(
+        &mut self,
+        frame: &Frame,
+        displayed: Option<&Frame>,
+        caret: Option<Position>,
+    ) -> Result<(), Error> {
+        let mut screen = self.screen.borrow_mut();
+        screen.repainted = frame.changes(displayed).map(|(position, _)| position).collect();
+        screen.frame = frame.clone();
+        screen.caret = caret;
+        Ok(())
+    }
+
+    fn print(&mut self, _text: &str) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::Terminal;
+    use super::*;
+
+    #[test]
+    fn draws_repaint_only_what_changed() -> Result<(), Error> {
+        let size = Size { height: 2, width: 3 };
+        let backend = MemoryBackend::new(size);
+        Terminal::set_backend(backend.clone());
+        Terminal::initialize()?;
+        assert_eq!(backend.repainted().len(), 6);
+
+        Terminal::print_row(0, "ab")?;
+        Terminal::print_row(1, "cd")?;
+        Terminal::move_caret_to(Position { col: 2, row: 1 })?;
+        Terminal::show_caret()?;
+        Terminal::execute()?;
+        assert_eq!(backend.text(), ["ab ", "cd "]);
+        assert_eq!(backend.caret(), Some(Position { col: 2, row: 1 }));
+        assert_eq!(backend.repainted().len(), 4);
+
+        Terminal::print_row(1, "ce")?;
+        Terminal::execute()?;
+        assert_eq!(backend.text(), ["ab ", "ce "]);
+        assert_eq!(backend.repainted(), [Position { col: 1, row: 1 }]);
+
+        Terminal::hide_caret()?;
+        Terminal::execute()?;
+        assert_eq!(backend.caret(), None);
+        assert!(backend.repainted().is_empty());
+
+        // A resize repaints everything.
+        Terminal::resize(size);
+        Terminal::print_row(0, "ab")?;
+        Terminal::print_row(1, "ce")?;
+        Terminal::execute()?;
+        assert_eq!(backend.repainted().len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn clones_share_the_screen() -> Result<(), Error> {
+        let backend = MemoryBackend::new(Size { height: 1, width: 2 });
+        let mut clone = backend.clone();
+        clone.set_title("hecto")?;
+        assert_eq!(backend.title(), "hecto");
+
+        backend.set_size(Size { height: 3, width: 4 });
+        assert_eq!(clone.size()?, Size { height: 3, width: 4 });
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use std::io::Error;
+
+use crate::prelude::*;
+
+use super::{ColorDepth, Frame};
+
+mod crosstermbackend;
+#[cfg(test)]
+mod memorybackend;
+pub use crosstermbackend::CrosstermBackend;
+#[cfg(test)]
+pub use memorybackend::MemoryBackend;
+
+/// The device the editor draws on. `CrosstermBackend` drives the real
+/// terminal, `MemoryBackend` keeps the screen in memory.
+///
+/// Fallible methods return the I/O error of the underlying device.
+#[allow(clippy::missing_errors_doc)]
+pub trait Backend {
+    fn initialize(&mut self) -> Result<(), Error>;
+
+    fn terminate(&mut self) -> Result<(), Error>;
+
+    fn size(&self) -> Result<Size, Error>;
+
+    fn set_title(&mut self, title: &str) -> Result<(), Error>;
+
+    /// Brings the screen in line with `frame`. `displayed` is what the
+    /// previous call left on screen, or `None` if it must be repainted in full.
+    fn draw(
+        &mut self,
+        frame: &Frame,
+        displayed: Option<&Frame>,
+        caret: Option<Position>,
+    ) -> Result<(), Error>;
+
+    /// Writes outside of the frame, e.g. after the editor has terminated.
+    fn print(&mut self, text: &str) -> Result<(), Error>;
+
+    fn set_color_depth(&mut self, _color_depth: ColorDepth) {}
+
+    fn set_styled_underline(&mut self, _enabled: bool) {}
+}
\ No newline at end of file
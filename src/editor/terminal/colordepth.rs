@@ -0,0 +1,152 @@
+use std::env;
+
+use crossterm::style::Color;
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    #[must_use]
+    pub fn detect() -> Self {
+        let color_term = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if matches!(color_term.as_str(), "truecolor" | "24bit") || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" | "rgb" => Some(Self::TrueColor),
+            "256" | "ansi256" => Some(Self::Ansi256),
+            "16" | "ansi16" | "ansi" => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn downsample(self, color: Color) -> Color {
+        let Some(rgb) = to_rgb(color) else {
+            return color;
+        };
+        match self {
+            Self::TrueColor => color,
+            Self::Ansi256 => Color::AnsiValue(nearest_ansi_256(rgb)),
+            Self::Ansi16 => nearest_ansi_16(rgb),
+        }
+    }
+}
+
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(value) if value >= 16 => Some(ansi_256_to_rgb(value)),
+        _ => None,
+    }
+}
+
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+fn ansi_256_to_rgb(value: u8) -> (u8, u8, u8) {
+    if value >= 232 {
+        let level = value.saturating_sub(232).saturating_mul(10).saturating_add(8);
+        return (level, level, level);
+    }
+    let cube_idx = usize::from(value.saturating_sub(16));
+    let level = |idx: usize| CUBE_LEVELS.get(idx % 6).copied().unwrap_or_default();
+    (level(cube_idx / 36), level(cube_idx / 6), level(cube_idx))
+}
+
+#[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+fn nearest_ansi_256(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255_u16)
+        .map(|value| value as u8)
+        .min_by_key(|value| distance(rgb, ansi_256_to_rgb(*value)))
+        .unwrap_or_default()
+}
+
+fn nearest_ansi_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+// Weighted ("redmean") Euclidean distance, a cheap approximation of
+// perceived colour difference.
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let red_mean = u32::from(r1).midpoint(u32::from(r2));
+    let dr = u32::from(r1.abs_diff(r2));
+    let dg = u32::from(g1.abs_diff(g2));
+    let db = u32::from(b1.abs_diff(b2));
+    (((512 + red_mean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - red_mean) * db * db) >> 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(ColorDepth::from_name("24bit"), Some(ColorDepth::TrueColor));
+        assert_eq!(ColorDepth::from_name("ANSI256"), Some(ColorDepth::Ansi256));
+        assert_eq!(ColorDepth::from_name("16"), Some(ColorDepth::Ansi16));
+        assert_eq!(ColorDepth::from_name("mono"), None);
+    }
+
+    #[test]
+    fn true_color_is_kept() {
+        let color = Color::Rgb { r: 1, g: 2, b: 3 };
+        assert_eq!(ColorDepth::TrueColor.downsample(color), color);
+    }
+
+    #[test]
+    fn rgb_is_mapped_to_the_nearest_palette_color() {
+        let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+        assert_eq!(ColorDepth::Ansi256.downsample(orange), Color::AnsiValue(208));
+        assert_eq!(ColorDepth::Ansi16.downsample(orange), Color::DarkYellow);
+
+        let grey = Color::Rgb { r: 128, g: 128, b: 128 };
+        assert_eq!(ColorDepth::Ansi256.downsample(grey), Color::AnsiValue(244));
+        assert_eq!(ColorDepth::Ansi16.downsample(grey), Color::DarkGrey);
+    }
+
+    #[test]
+    fn palette_colors_are_downsampled_too() {
+        assert_eq!(ColorDepth::Ansi16.downsample(Color::AnsiValue(196)), Color::Red);
+        assert_eq!(ColorDepth::Ansi16.downsample(Color::AnsiValue(3)), Color::AnsiValue(3));
+        assert_eq!(ColorDepth::Ansi256.downsample(Color::Blue), Color::Blue);
+    }
+}
\ No newline at end of file
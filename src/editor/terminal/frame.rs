@@ -0,0 +1,168 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::prelude::*;
+
+use super::{Attribute, ScreenCell};
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    size: Size,
+    cells: Vec<ScreenCell>,
+}
+
+impl Frame {
+    #[must_use]
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells: vec![ScreenCell::default(); size.width.saturating_mul(size.height)],
+        }
+    }
+
+    #[cfg(test)]
+    #[must_use]
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get(&self, position: Position) -> Option<&ScreenCell> {
+        if position.col >= self.size.width {
+            return None;
+        }
+        self.cells.get(self.index(position))
+    }
+
+    /// The cells that differ from `displayed`, in drawing order, without the
+    /// right halves of wide characters. All of them if nothing is displayed
+    /// or it has another size.
+    pub fn changes<'a>(
+        &'a self,
+        displayed: Option<&'a Self>,
+    ) -> impl Iterator<Item = (Position, &'a ScreenCell)> + 'a {
+        let displayed = displayed.filter(|displayed| displayed.size == self.size);
+        (0..self.size.height)
+            .flat_map(move |row| (0..self.size.width).map(move |col| Position { col, row }))
+            .filter_map(move |position| {
+                let cell = self.get(position)?;
+                let unchanged = displayed
+                    .and_then(|displayed| displayed.get(position))
+                    .is_some_and(|shown| shown == cell);
+                (!unchanged && !cell.is_continuation()).then_some((position, cell))
+            })
+    }
+
+    pub fn write_row<'a>(
+        &mut self,
+        row: RowIdx,
+        parts: impl IntoIterator<Item = (&'a str, Attribute)>,
+        fill: Attribute,
+    ) {
+        if row >= self.size.height {
+            return;
+        }
+        let width = self.size.width;
+        let mut col: ColIdx = 0;
+
+        'parts: for (text, attribute) in parts {
+            for grapheme in text.graphemes(true) {
+                let (symbol, cell_width) = if grapheme.chars().any(char::is_control) {
+                    (" ", 1)
+                } else if grapheme.width() > 1 {
+                    (grapheme, 2)
+                } else {
+                    (grapheme, 1)
+                };
+                if col.saturating_add(cell_width) > width {
+                    break 'parts;
+                }
+                self.set(
+                    Position { col, row },
+                    ScreenCell {
+                        symbol: symbol.to_string(),
+                        attribute,
+                    },
+                );
+                if cell_width == 2 {
+                    self.set(
+                        Position {
+                            row,
+                            col: col.saturating_add(1),
+                        },
+                        ScreenCell::continuation(attribute),
+                    );
+                }
+                col = col.saturating_add(cell_width);
+            }
+        }
+
+        for col in col..width {
+            self.set(Position { col, row }, ScreenCell::blank(fill));
+        }
+    }
+
+    fn set(&mut self, position: Position, cell: ScreenCell) {
+        let idx = self.index(position);
+        if let Some(current) = self.cells.get_mut(idx) {
+            *current = cell;
+        }
+    }
+
+    fn index(&self, position: Position) -> usize {
+        position
+            .row
+            .saturating_mul(self.size.width)
+            .saturating_add(position.col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(rows: &[&str]) -> Frame {
+        let mut frame = Frame::new(Size { height: rows.len(), width: 4 });
+        for (row, text) in rows.iter().enumerate() {
+            frame.write_row(row, [(*text, Attribute::default())], Attribute::default());
+        }
+        frame
+    }
+
+    fn symbols(frame: &Frame, row: RowIdx) -> Vec<&str> {
+        (0..frame.size().width)
+            .filter_map(|col| frame.get(Position { col, row }))
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn rows_are_cut_and_padded_to_the_width() {
+        let frame = frame(&["abcdef", "a\tb", "ab中文"]);
+        assert_eq!(symbols(&frame, 0), ["a", "b", "c", "d"]);
+        assert_eq!(symbols(&frame, 1), ["a", " ", "b", " "]);
+        // The second wide character would not fit.
+        assert_eq!(symbols(&frame, 2), ["a", "b", "中", ""]);
+    }
+
+    #[test]
+    fn changes_leave_out_what_is_displayed_already() {
+        let displayed = frame(&["ab", "cd"]);
+        let next = frame(&["ab", "c中"]);
+
+        let changes: Vec<_> = next
+            .changes(Some(&displayed))
+            .map(|(position, cell)| (position, cell.symbol.as_str()))
+            .collect();
+        assert_eq!(changes, [(Position { col: 1, row: 1 }, "中")]);
+        assert_eq!(next.changes(Some(&next)).count(), 0);
+    }
+
+    #[test]
+    fn everything_changes_without_a_matching_display() {
+        let displayed = frame(&["ab"]);
+        let next = frame(&["ab", "中"]);
+        assert_eq!(next.changes(None).count(), 7);
+        assert_eq!(next.changes(Some(&displayed)).count(), 7);
+    }
+}
\ No newline at end of file
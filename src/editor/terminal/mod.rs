@@ -1,165 +1,157 @@
 mod attribute;
+mod backend;
+mod colordepth;
+mod frame;
+mod screencell;
+mod underline;
 use crate::prelude::*;
-use attribute::Attribute;
-use crossterm::cursor::{Hide, MoveTo, Show};
-use crossterm::style::{
-    Attribute::{Reset, Reverse},
-    Print, ResetColor, SetBackgroundColor, SetForegroundColor,
-};
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, size, Clear, ClearType, DisableLineWrap, EnableLineWrap,
-    EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
-};
-use crossterm::{queue, Command};
-use std::io::{stdout, Error, Write};
-
+pub use attribute::Attribute;
+pub use backend::{Backend, CrosstermBackend};
+#[cfg(test)]
+pub use backend::MemoryBackend;
+pub use colordepth::ColorDepth;
+pub use frame::Frame;
+pub use screencell::ScreenCell;
+pub use underline::{detect_styled_underline, Underline};
+use std::cell::RefCell;
+use std::io::Error;
+
+use super::theme::{Theme, ThemeElement};
 use super::AnnotatedString;
 
+thread_local! {
+    static THEME: RefCell<Theme> = RefCell::new(Theme::default());
+    static BACKEND: RefCell<Box<dyn Backend>> = RefCell::new(Box::new(CrosstermBackend::default()));
+    static RENDERER: RefCell<Renderer> = RefCell::new(Renderer::default());
+}
+
+#[derive(Default)]
+struct Renderer {
+    frame: Frame,
+    screen: Option<Frame>,
+    caret: Position,
+    caret_visible: bool,
+}
 
 pub struct Terminal;
 
 impl Terminal {
+    pub fn set_backend(backend: impl Backend + 'static) {
+        BACKEND.with_borrow_mut(|current| *current = Box::new(backend));
+        RENDERER.with_borrow_mut(|renderer| *renderer = Renderer::default());
+    }
+
     pub fn terminate() -> Result<(), Error> {
-        Self::leave_alternate_screen()?;
-        Self::enable_line_wrap()?;
-        Self::show_caret()?;
-        Self::execute()?;
-        disable_raw_mode()?;
-        Ok(())
+        BACKEND.with_borrow_mut(|backend| backend.terminate())
     }
 
     pub fn initialize() -> Result<(), Error> {
-        enable_raw_mode()?;
-        Self::enter_alternate_screen()?;
-        Self::disable_line_wrap()?;
-        Self::clear_screen()?;
+        BACKEND.with_borrow_mut(|backend| backend.initialize())?;
+        Self::resize(Self::size()?);
         Self::execute()?;
         Ok(())
     }
 
-    pub fn clear_screen() -> Result<(), Error> {
-        Self::queue_command(Clear(ClearType::All))?;
-        Ok(())
+    pub fn resize(size: Size) {
+        RENDERER.with_borrow_mut(|renderer| {
+            renderer.frame = Frame::new(size);
+            renderer.screen = None;
+        });
     }
 
-    pub fn clear_line() -> Result<(), Error> {
-        Self::queue_command(Clear(ClearType::CurrentLine))?;
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn move_caret_to(position: Position) -> Result<(), Error> {
+        RENDERER.with_borrow_mut(|renderer| renderer.caret = position);
         Ok(())
     }
 
-    pub fn move_caret_to(position: Position) -> Result<(), Error> {
-        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
-        Self::queue_command(MoveTo(position.col as u16, position.row as u16))?;
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn hide_caret() -> Result<(), Error> {
+        RENDERER.with_borrow_mut(|renderer| renderer.caret_visible = false);
         Ok(())
     }
 
-    pub fn enter_alternate_screen() -> Result<(), Error> {
-        Self::queue_command(EnterAlternateScreen)?;
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn show_caret() -> Result<(), Error> {
+        RENDERER.with_borrow_mut(|renderer| renderer.caret_visible = true);
         Ok(())
     }
 
-    pub fn leave_alternate_screen() -> Result<(), Error> {
-        Self::queue_command(LeaveAlternateScreen)?;
-        Ok(())
+    pub fn set_title(title: &str) -> Result<(), Error> {
+        BACKEND.with_borrow_mut(|backend| backend.set_title(title))
     }
 
-    pub fn hide_caret() -> Result<(), Error> {
-        Self::queue_command(Hide)?;
-        Ok(())
+    pub fn print(string: &str) -> Result<(), Error> {
+        BACKEND.with_borrow_mut(|backend| backend.print(string))
     }
 
-    pub fn show_caret() -> Result<(), Error> {
-        Self::queue_command(Show)?;
-        Ok(())
+    pub fn set_theme(theme: Theme) {
+        THEME.with_borrow_mut(|current| *current = theme);
     }
 
-    pub fn disable_line_wrap() -> Result<(), Error> {
-        Self::queue_command(DisableLineWrap)?;
-        Ok(())
+    pub fn set_color_depth(color_depth: ColorDepth) {
+        BACKEND.with_borrow_mut(|backend| backend.set_color_depth(color_depth));
     }
 
-    pub fn enable_line_wrap() -> Result<(), Error> {
-        Self::queue_command(EnableLineWrap)?;
-        Ok(())
+    pub fn set_styled_underline(enabled: bool) {
+        BACKEND.with_borrow_mut(|backend| backend.set_styled_underline(enabled));
     }
 
-    pub fn set_title(title: &str) -> Result<(), Error> {
-        Self::queue_command(SetTitle(title))?;
-        Ok(())
+    pub fn themed_attribute(element: ThemeElement) -> Attribute {
+        THEME.with_borrow(|theme| theme.attribute(element))
     }
 
-    pub fn print(string: &str) -> Result<(), Error> {
-        Self::queue_command(Print(string))?;
-        Ok(())
+    fn base_attribute(element: ThemeElement) -> Attribute {
+        Self::themed_attribute(ThemeElement::Text).merge(Self::themed_attribute(element))
     }
 
     pub fn print_row(row: RowIdx, line_text: &str) -> Result<(), Error> {
-        Self::move_caret_to(Position { row, col: 0 })?;
-        Self::clear_line()?;
-        Self::print(line_text)?;
+        Self::print_themed_row(row, line_text, ThemeElement::Text)
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn print_themed_row(
+        row: RowIdx,
+        line_text: &str,
+        element: ThemeElement,
+    ) -> Result<(), Error> {
+        let base = Self::base_attribute(element);
+        RENDERER.with_borrow_mut(|renderer| {
+            renderer.frame.write_row(row, [(line_text, base)], base);
+        });
         Ok(())
     }
 
+    #[allow(clippy::unnecessary_wraps)]
     pub fn print_annotated_row(
         row: RowIdx,
         annotated_string: &AnnotatedString,
+        element: ThemeElement,
     ) -> Result<(),Error>{
-        Self::move_caret_to(Position{row,col:0})?;
-        Self::clear_line()?;
-        annotated_string
-        .into_iter()
-        .try_for_each(|part| -> Result<(),Error>{
-            if let Some(annotation_type) = part.annotation_type {
-                let attribute: Attribute = annotation_type.into();
-                Self::set_attribute(&attribute)?;
-            }
-            Self::print(part.string)?;
-            Self::reset_color()?;
-            Ok(())
-        })?;
-        Ok(())
-    }
-
-    fn set_attribute(attribute: &Attribute) -> Result<(),Error> {
-        if let Some(foreground_color) = attribute.foreground {
-            Self::queue_command(SetForegroundColor(foreground_color))?;
-        }
-
-        if let Some(background_color) = attribute.background {
-            Self::queue_command(SetBackgroundColor(background_color))?;
-        }
-
-        Ok(())
-    }
-
-    fn reset_color() -> Result<(),Error>{
-        Self::queue_command(ResetColor)?;
+        let base = Self::base_attribute(element);
+        let parts = annotated_string.into_iter().map(|part| {
+            let attribute = part.annotation_type.map_or(base, |annotation_type| {
+                base.merge(Self::themed_attribute(annotation_type.into()))
+            });
+            (part.string, attribute)
+        });
+        let parts: Vec<(&str, Attribute)> = parts.collect();
+        RENDERER.with_borrow_mut(|renderer| renderer.frame.write_row(row, parts, base));
         Ok(())
     }
 
-    pub fn print_inverted_row(row: RowIdx, line_text: &str) -> Result<(), Error> {
-        let width = Self::size()?.width;
-        Terminal::print_row(row,&format!("{Reverse}{line_text:width$.width$}{Reset}"))
-    }
-
     pub fn size() -> Result<Size, Error> {
-        let (width_u16, height_u16) = size()?;
-
-        #[allow(clippy::as_conversions)]
-        let height = height_u16 as usize;
-
-        #[allow(clippy::as_conversions)]
-        let width = width_u16 as usize;
-        Ok(Size { height, width })
+        BACKEND.with_borrow(|backend| backend.size())
     }
 
     pub fn execute() -> Result<(), Error> {
-        stdout().flush()?;
-        Ok(())
-    }
-
-    fn queue_command<T: Command>(command: T) -> Result<(), Error> {
-        queue!(stdout(), command)?;
-        Ok(())
+        RENDERER.with_borrow_mut(|renderer| {
+            let caret = renderer.caret_visible.then_some(renderer.caret);
+            BACKEND.with_borrow_mut(|backend| {
+                backend.draw(&renderer.frame, renderer.screen.as_ref(), caret)
+            })?;
+            renderer.screen = Some(renderer.frame.clone());
+            Ok(())
+        })
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use super::Attribute;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScreenCell {
+    pub symbol: String,
+    pub attribute: Attribute,
+}
+
+impl ScreenCell {
+    #[must_use]
+    pub fn blank(attribute: Attribute) -> Self {
+        Self {
+            symbol: " ".to_string(),
+            attribute,
+        }
+    }
+
+    #[must_use]
+    pub fn continuation(attribute: Attribute) -> Self {
+        Self {
+            symbol: String::new(),
+            attribute,
+        }
+    }
+
+    #[must_use]
+    pub fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+}
+
+impl Default for ScreenCell {
+    fn default() -> Self {
+        Self::blank(Attribute::default())
+    }
+}
\ No newline at end of file
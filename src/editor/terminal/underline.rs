@@ -0,0 +1,38 @@
+use std::env;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Underline {
+    Straight,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl Underline {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "underline" => Some(Self::Straight),
+            "double_underline" => Some(Self::Double),
+            "curly_underline" | "undercurl" => Some(Self::Curly),
+            "dotted_underline" => Some(Self::Dotted),
+            "dashed_underline" => Some(Self::Dashed),
+            _ => None,
+        }
+    }
+}
+
+pub fn detect_styled_underline() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let vte_version = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .unwrap_or_default();
+
+    ["kitty", "wezterm", "foot", "alacritty", "ghostty", "contour"]
+        .iter()
+        .any(|name| term.contains(name))
+        || matches!(term_program.as_str(), "WezTerm" | "iTerm.app" | "ghostty")
+        || vte_version >= 5102
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+// Drawing text on screen, built on the `test-graphemes-*` and
+// `test-character-replacement.txt` files in the repository root.
+use super::run;
+
+#[test]
+fn graphemes_are_edited_and_saved_as_whole_clusters() {
+    run(r#"
+size 100 20
+open test-graphemes-1.txt
+expect-title "test-graphemes-1.txt - hecto"
+expect-line-count 12
+key Right; key Right; key Right; key Right; key Right
+expect-caret 1 6
+type "!"
+expect-line 1 "Lörëm! ípsüm dólör sït âmét, cönsëctetur âdïpïscïng élit. "
+key Down; key Down; key Down; key Down; key Down; key Down
+key Home; key Right; key Right; key Right
+expect-caret 7 4
+key Backspace
+expect-caret 7 3
+key Ctrl-S
+expect-row 20 "File saved successfully."
+expect-file-line 1 "Lörëm! ípsüm dólör sït âmét, cönsëctetur âdïpïscïng élit. "
+expect-file-line 2 "Sëd dö ēiusmód tēmpör ïncïdïdünt üt lâborë ēt dölöre mägnä äliquä."
+"#);
+}
+
+#[test]
+fn long_lines_scroll_horizontally() {
+    run(r#"
+size 40 20
+open test-graphemes-2.txt
+key Down; key End
+expect-row 2 "g élit.  End of Line, scroll until here"
+expect-caret 2 40
+key Down; key Down; key Down; key Down; key Down; key Down; key End
+expect-row 8 "ＷＸＹＺ End of Line, scroll until here"
+key Home
+expect-row 8 "ＡＢＣＤＥＦＧＨＩＪＫＬＭＮＯＰＱＲＳＴ"
+expect-caret 8 1
+"#);
+}
+
+#[test]
+fn invisible_and_control_characters_are_replaced() {
+    run(r#"
+size 60 30
+open test-character-replacement.txt
+expect-row 5 "Here    is  a   tab separated   line."
+expect-row 8 "Non-breaking␣space and zero-width·space."
+expect-row 11 "Control characters:▯[Escape]▯[Bell]"
+expect-row 14 "Family emoji: 👨‍👩‍👧‍👦"
+expect-row 17 "全角文字"
+expect-row 20 "Combining: e\u{301} (e with acute accent)"
+"#);
+}
+
+#[test]
+fn rows_past_the_end_are_drawn_in_the_gutter_style() {
+    run(r#"
+open-text "one\n"
+expect-row 2 "~"
+expect-row-style 2 gutter
+expect-row-style 1 caret_line
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,99 @@
+// Text encodings and the hex view of binary files.
+use super::run;
+
+#[test]
+fn legacy_files_are_decoded_and_saved_back_unchanged() {
+    run(r#"
+write-bytes legacy.txt "63 61 66 e9 0a"
+open legacy.txt
+expect-line 1 "café"
+expect-status-contains "| Latin-1 |"
+type "!"
+key Ctrl-S
+expect-bytes "21 63 61 66 e9 0a"
+"#);
+}
+
+#[test]
+fn utf16_files_are_detected_with_and_without_a_bom() {
+    run(r#"
+write-bytes wide.txt "ff fe 68 00 69 00 0a 00"
+open wide.txt
+expect-line 1 "hi"
+expect-status-contains "| UTF-16LE |"
+type "o"
+key Ctrl-S
+expect-bytes "ff fe 6f 00 68 00 69 00 0a 00"
+write-bytes bare.txt "00 68 00 69 00 0a"
+open bare.txt
+expect-line 1 "hi"
+expect-status-contains "| UTF-16BE |"
+"#);
+}
+
+#[test]
+fn utf8_bom_is_kept_out_of_the_text() {
+    run(r#"
+write-bytes bom.txt "ef bb bf 61 0a"
+open bom.txt
+expect-line 1 "a"
+expect-status-contains "| UTF-8 BOM |"
+type "b"
+key Ctrl-S
+expect-bytes "ef bb bf 62 61 0a"
+"#);
+}
+
+#[test]
+fn encoding_can_be_changed() {
+    run(r#"
+write-file a.txt "é\n"
+open a.txt
+key Ctrl-O; type "utf-16be"; key Enter
+expect-row 24 "Encoding set to UTF-16BE."
+expect-status-contains "(modified)"
+expect-status-contains "| UTF-16BE |"
+key Ctrl-S
+expect-bytes "fe ff 00 e9 00 0a"
+key Ctrl-O; type "latin1"; key Enter
+type "€"
+key Ctrl-S
+expect-row 24 "Error writing file: '€' cannot be saved as Latin-1."
+key Ctrl-O; type "koi8"; key Enter
+expect-row 24 "Unknown encoding: koi8"
+"#);
+}
+
+#[test]
+fn binary_files_open_in_a_hex_view() {
+    run(r#"
+write-bytes bin.dat "48 69 00 ff 00 00 00 00 00 00 00 00 00 00 00 00 41"
+open bin.dat
+expect-row 1 "00000000  48 69 00 ff 00 00 00 00  00 00 00 00 00 00 00 00  |Hi..............|"
+expect-row 2 "00000010  41                                                |A|"
+expect-row 3 "~"
+expect-status-contains "17 bytes"
+expect-status-contains "Hex | 00000000/00000011"
+expect-caret 1 11
+key Right
+expect-caret 1 14
+key Down
+expect-caret 2 11
+type "7"
+expect-caret 2 12
+type "a"
+expect-caret 2 11
+expect-row 2 "00000010  7a                                                |z|"
+expect-status-contains "(modified)"
+expect-status-contains "00000010/00000011"
+type "g"; key Enter; key Backspace
+key Ctrl-S
+expect-bytes "48 69 00 ff 00 00 00 00 00 00 00 00 00 00 00 00 7a"
+key Up; key End
+expect-caret 1 57
+click 1 64
+expect-caret 1 17
+key Ctrl-E
+expect-row 24 "Binary files have no line endings or encoding."
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,77 @@
+// Files that change on disk while they are open.
+use super::run;
+
+#[test]
+fn unmodified_buffers_reload_silently_when_the_file_changes() {
+    run(r#"
+write-file a.txt "one\ntwo\n"
+open a.txt
+key Down
+write-file a.txt "one\nTWO\nthree\n"
+focus
+expect-line 2 "TWO"
+expect-line-count 3
+expect-caret 2 1
+"#);
+}
+
+#[test]
+fn saving_over_an_external_change_asks_first() {
+    run(r#"
+write-file a.txt "one\ntwo\n"
+open a.txt
+type "x"
+write-file a.txt "one\nTWO\nthree\n"
+key Ctrl-S
+expect-row 24 "File changed on disk! r = reload, o = overwrite, d = diff, Esc = cancel"
+type "d"
+expect-row 1 "-one"
+expect-row 4 "+xone"
+expect-row 5 "+two"
+key Esc
+expect-row 24 "File on disk left unchanged."
+expect-line 1 "xone"
+expect-file "one\nTWO\nthree\n"
+key Ctrl-S; type "o"
+expect-row 24 "File saved successfully."
+expect-file "xone\ntwo\n"
+"#);
+}
+
+#[test]
+fn modified_buffers_can_reload_when_focus_returns() {
+    run(r#"
+write-file a.txt "one\n"
+open a.txt
+type "x"
+write-file a.txt "uno\n"
+focus
+expect-row 24 "File changed on disk! r = reload, o = overwrite, d = diff, Esc = cancel"
+type "r"
+expect-row 24 "File reloaded from disk."
+expect-line 1 "uno"
+expect-status-lacks "(modified)"
+"#);
+}
+
+#[test]
+fn changes_on_disk_are_noticed_while_idle() {
+    run(r#"
+write-file a.txt "one\n"
+open a.txt
+idle 5
+write-file a.txt "uno dos\n"
+idle 5
+expect-line 1 "uno dos"
+type "x"
+write-file a.txt "eins\n"
+idle 5
+expect-row 24 "File changed on disk! r = reload, o = overwrite, d = diff, Esc = cancel"
+key Esc
+idle 5
+expect-row 24 "File on disk left unchanged."
+write-file a.txt "un\n"
+idle 5
+expect-row 24 "File changed on disk! r = reload, o = overwrite, d = diff, Esc = cancel"
+"#);
+}
\ No newline at end of file
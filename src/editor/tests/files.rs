@@ -0,0 +1,113 @@
+// Opening files: read-only mode, several files, unnamed text and pipe
+// mode.
+use super::run;
+
+#[test]
+#[cfg(unix)]
+fn unwritable_files_open_read_only_but_can_be_saved_as() {
+    run(r#"
+write-file a.txt "one\n"
+chmod a.txt 444
+open a.txt
+expect-status-contains "a.txt [RO]"
+type "x"
+expect-row 24 "File is read-only. Press Ctrl-R to allow editing."
+expect-line 1 "one"
+key Ctrl-S
+expect-row 24 "Save as:"
+type "$SCRATCH/b.txt"; key Enter
+expect-row 24 "File saved successfully."
+expect-file-at b.txt "one\n"
+expect-status-contains "b.txt - 1 lines"
+"#);
+}
+
+#[test]
+fn read_only_mode_can_be_toggled() {
+    run(r#"
+write-file a.txt "one\n"
+open a.txt
+key Ctrl-R
+expect-row 24 "Read-only mode on."
+key Ctrl-E
+expect-row 24 "File is read-only. Press Ctrl-R to allow editing."
+key Ctrl-R
+expect-row 24 "Read-only mode off."
+type "x"
+expect-line 1 "xone"
+"#);
+}
+
+#[test]
+fn several_files_can_be_open_and_switched_between() {
+    run(r#"
+write-file a.txt "one\n"
+write-file b.txt "two\nlines\n"
+open a.txt
+open b.txt 2 3
+expect-status-contains "b.txt (2/2)"
+expect-caret 2 3
+key Ctrl-N
+expect-status-contains "a.txt (1/2)"
+type "x"
+key Ctrl-P
+expect-line 1 "two"
+key Ctrl-Q
+expect-row 24 "WARNING! File has unsaved changes. Press Ctrl-Q 2 more times to quit."
+key Ctrl-N
+expect-line 1 "xone"
+"#);
+}
+
+#[test]
+fn text_without_a_file_opens_unnamed() {
+    run(r#"
+open-text "piped\ninput\n"
+expect-status-contains "[No Name] - 2 lines"
+expect-line 2 "input"
+key Ctrl-N
+expect-row 24 "No other files are open."
+"#);
+}
+
+#[test]
+fn pipe_mode_quits_with_the_edited_text() {
+    run(r#"
+pipe-mode
+open-text "piped\ninput\n"
+type "edited "
+key Ctrl-Q
+expect-quit
+expect-text "edited piped\ninput\n"
+"#);
+}
+
+#[test]
+fn piped_input_is_decoded_and_written_back_in_its_encoding() {
+    run(r#"
+pipe-mode
+open-bytes "63 61 66 e9 0a"
+expect-line 1 "café"
+expect-status-contains "Text | Latin-1 |"
+type "!"
+expect-text-bytes "21 63 61 66 e9 0a"
+"#);
+}
+
+#[test]
+fn piped_scripts_are_recognised_by_their_shebang() {
+    run(r##"
+open-text "#!/bin/sh\necho hi\n"
+expect-status-contains "Shell | UTF-8 |"
+"##);
+}
+
+#[test]
+fn piped_binary_input_opens_in_the_hex_view() {
+    run(r#"
+pipe-mode
+open-bytes "48 69 00 ff"
+expect-status-contains "Hex"
+expect-text-bytes "48 69 00 ff"
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,146 @@
+// Syntax, search and selection highlighting. The Rust fixtures in the
+// repository root keep their examples in line comments, so the tests first
+// delete the leading `// ` of the lines they check.
+use super::run;
+
+#[test]
+fn characters_are_highlighted() {
+    run(r#"
+size 100 10
+open test-character-highlighting.rs
+key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+expect-line 2 "'1' 'a' 'b' '👍' '\\x1b', 'notacharacter' '\\'', '\\\\' '1''2''3' '1'notchar'2'"
+expect-style 2 "'a'" char
+expect-style 2 "'👍'" char
+expect-style 2 "'\\x1b'" char
+expect-style 2 "'\\''" char
+expect-style 2 "notchar" text
+expect-style 4 "\"a\"" string
+expect-style 1 "Valid" comment
+"#);
+}
+
+#[test]
+fn lifetime_specifiers_are_highlighted() {
+    run(r#"
+size 100 10
+open test-lifetime-specifier-highlighting.rs
+key Down; key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+expect-style 4 "'a" lifetime_specifier
+expect-style 4 "'this_is_cool" lifetime_specifier
+expect-style 4 "'abc" lifetime_specifier
+expect-style 4 "<" text
+expect-style 6 "-specifier" text
+"#);
+}
+
+#[test]
+fn numbers_are_highlighted() {
+    run(r#"
+size 100 30
+open test-numeric-literal-highlighting.rs
+key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Down; key Down; key Delete; key Delete; key Delete
+key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up; key Up
+expect-style 2 "100" number
+expect-style 4 "9.0" number
+expect-style 6 "10.3e5" number
+expect-style 8 "1_000_000_000" number
+expect-style 10 "0X10F" number
+expect-style 14 "100a200b300c400d500" text
+expect-style 14 "u32" type
+expect-style 16 "1.1.2" text
+expect-style 18 "1e2e" text
+expect-style 20 "_100_1" text
+expect-style 22 "0x1G" text
+"#);
+}
+
+#[test]
+fn multi_line_comments_are_highlighted() {
+    run(r#"
+size 200 30
+open test-ml-highlighting.rs
+key PageDown
+expect-style 3 "which goes on" comment
+expect-style 8 "struct" keyword
+expect-style 8 "/* ml comments do not have to span multiple lines */" comment
+expect-style 9 "bar;" text
+expect-style 11 "" text
+expect-style 13 "and end in the middle of a line*/" comment
+expect-style 13 "f00;" text
+expect-style 16 "- keywords like struct" comment
+expect-style 24 "and once they end, the original comment is still there." comment
+expect-style 27 "not_part_of_comment;" text
+expect-style 27 "/* part of a comment */" comment
+"#);
+}
+
+#[test]
+fn strings_are_highlighted() {
+    run(r#"
+size 100 20
+open test-string-highlighting.rs
+key PageDown
+expect-style 2 "\"Hello, World!\"" string
+expect-style 3 "\"Hello \\\"Hello\\\" Hello!\"" string
+expect-style 5 "\"\\\"Hello\\\"\"" string
+expect-style 6 "\"\"" string
+expect-style 8 "World!\"" string
+expect-style 8 ";" text
+expect-style 10 "/* this is not actually a ML comment." string
+expect-style 13 "It looks like a multi line string ends here: \"" comment
+"#);
+}
+
+#[test]
+fn edits_are_highlighted_again_from_the_changed_line_on() {
+    run(r#"
+write-file a.rs "fn a() {}\nlet b = 1;\n"
+open a.rs
+type "/*"
+expect-style 2 "let" comment
+key Backspace
+expect-style 2 "let" keyword
+write-file a.json "{\n  \"a\": 1,\n  \"b\": 2\n}\n"
+open a.json
+key Down; key Down; key Right; key Right; key Right; key Delete; type "a"
+expect-style 3 "\"a\"" error
+key Up; key Backspace; type "c"
+expect-line 2 "  \"c\": 1,"
+expect-style 3 "\"a\"" key
+"#);
+}
+
+#[test]
+fn search_results_are_highlighted() {
+    run(r#"
+size 200 20
+open test-search-highlighting.txt
+key Down; key Down
+key Ctrl-F
+type "needle"
+expect-caret 20 49
+expect-style 1 "needle" match
+expect-style 3 "needle" selected_match
+expect-style 4 "need le" text
+key Down
+expect-style 3 "needle" match
+expect-style 6 "needle" selected_match
+expect-style 9 "needle" match
+expect-style 10 "needle" match
+expect-style 15 "needle" match
+key Esc
+expect-caret 3 1
+"#);
+}
\ No newline at end of file
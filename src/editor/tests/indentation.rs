@@ -0,0 +1,155 @@
+// Auto-indentation, tabs and `.editorconfig` files.
+use super::run;
+
+#[test]
+fn new_lines_are_indented_by_file_type() {
+    run(r#"
+config "expand_tabs = on"
+key Ctrl-T; type "rust"; key Enter
+type "fn main() {\nlet x = [\n1,\n2,\n];\n}"
+expect-line 1 "fn main() {"
+expect-line 2 "    let x = ["
+expect-line 3 "        1,"
+expect-line 4 "        2,"
+expect-line 5 "    ];"
+expect-line 6 "}"
+expect-caret 6 2
+key Ctrl-T; type "python"; key Enter
+key Up; key End
+type "\nif x:\nreturn"
+expect-line 6 "    if x:"
+expect-line 7 "        return"
+expect-caret 7 15
+"#);
+}
+
+#[test]
+fn enter_between_brackets_puts_the_closing_one_on_its_own_line() {
+    run(r#"
+key Ctrl-T; type "rust"; key Enter
+type "fn main() {}"
+key Left; key Enter
+expect-line-count 3
+expect-line 1 "fn main() {"
+expect-line 2 "\t"
+expect-line 3 "}"
+expect-caret 2 5
+type "let x = [];"
+key Left; key Left; key Enter
+expect-line 2 "\tlet x = ["
+expect-line 3 "\t\t"
+expect-line 4 "\t];"
+expect-line 5 "}"
+expect-caret 3 9
+"#);
+}
+
+#[test]
+fn plain_text_only_keeps_the_indentation() {
+    run(r#"
+type "  one {\ntwo three"
+key Left; key Left; key Left; key Left; key Left; key Left
+type "\n"
+expect-line 2 "  two"
+expect-line 3 "  three"
+expect-caret 3 3
+type "}"
+expect-line 3 "  }three"
+"#);
+}
+
+#[test]
+fn tabs_advance_to_the_next_tab_stop() {
+    run(r#"
+size 40 20
+open test-character-replacement.txt
+key Down; key Down; key Down; key Down; key End
+expect-caret 5 38
+key Home; key Right; key Right; key Right; key Right; key Right
+expect-caret 5 9
+click 5 7
+expect-caret 5 5
+key Tab
+expect-line 5 "Here\t\tis\ta\ttab\tseparated\tline."
+expect-row 5 "Here        is  a   tab separated   line"
+"#);
+}
+
+#[test]
+fn tab_inserts_spaces_when_tabs_are_expanded() {
+    run(r#"
+key Ctrl-T; type "rust"; key Enter
+type "ab"; key Tab
+expect-line 1 "ab\t"
+key Backspace
+config "expand_tabs = on"
+key Tab; type "c"
+expect-line 1 "ab  c"
+key Home; key Tab
+expect-line 1 "    ab  c"
+expect-caret 1 5
+"#);
+}
+
+#[test]
+fn tab_and_shift_tab_indent_and_dedent_the_selection() {
+    run(r#"
+config "expand_tabs = on"
+key Ctrl-T; type "python"; key Enter
+type "a = 1\nb = 2\nc = 3\nd = 4"
+click 1 3
+drag 3 1
+key Tab
+expect-line 1 "    a = 1"
+expect-line 2 "    b = 2"
+expect-line 3 "c = 3"
+expect-style 1 "= 1" selection
+expect-style 2 "    b = 2" selection
+key Tab
+expect-line 1 "        a = 1"
+key Shift-Tab; key Shift-Tab; key Shift-Tab
+expect-line 1 "a = 1"
+expect-line 2 "b = 2"
+key Down; key Down; key Tab; key Shift-Tab
+expect-line 4 "d = 4"
+"#);
+}
+
+#[test]
+fn editorconfig_sections_are_matched_against_the_path() {
+    run(r#"
+write-file .editorconfig "root = true\n[*]\ntab_width = 2\n[{notes,other}.{txt,md}]\ntab_width = 8\n[sub/**]\ntab_width = 3\n[file{1..3}.txt]\ntab_width = 5\n[file[!2].txt]\ntab_width = 6\n"
+write-file notes.txt "a\tb"
+open notes.txt
+expect-row 1 "a       b"
+write-file other.rs "a\tb"
+open other.rs
+expect-row 1 "a b"
+write-file sub/deep/x.txt "a\tb"
+open sub/deep/x.txt
+expect-row 1 "a  b"
+write-file file2.txt "a\tb"
+open file2.txt
+expect-row 1 "a    b"
+write-file file3.txt "a\tb"
+open file3.txt
+expect-row 1 "a     b"
+"#);
+}
+
+#[test]
+fn editorconfig_settings_apply_to_editing_and_saving() {
+    run(r#"
+write-file .editorconfig "root = true\n\n[*.txt]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\ntrim_trailing_whitespace = true\ninsert_final_newline = false\n"
+write-file notes.txt "one  \ntwo\n"
+open notes.txt
+key Down; key Tab; type "x"
+key Ctrl-S
+expect-file "one\r\n  xtwo"
+expect-line 1 "one"
+key Up; key End; type "  "
+key Ctrl-S
+expect-line 1 "one"
+expect-caret 1 4
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+// Files over the large file threshold, which are read on demand.
+use super::run;
+
+#[test]
+fn large_files_are_indexed_and_read_on_demand() {
+    run(r#"
+config "large_file_size = 1K"
+write-numbered big.log 500
+open big.log
+wait-loaded
+expect-row 24 "Large file: without syntax highlighting, editable once indexed."
+expect-status-contains "big.log - 500 lines"
+expect-status-lacks "[RO]"
+expect-status-contains "| 1/500"
+expect-row 1 "line 1"
+expect-row 22 "line 22"
+key PageDown; key PageDown; key PageDown
+expect-status-contains "| 64/500"
+key Ctrl-F; type "line 420"; key Enter
+expect-status-contains "| 420/500"
+expect-caret 12 1
+expect-row 12 "line 420"
+key Ctrl-F; type "line 7"; key Enter
+expect-status-contains "| 7/500"
+key Ctrl-R
+expect-status-contains "[RO]"
+key Ctrl-R
+expect-status-lacks "[RO]"
+"#);
+}
+
+#[test]
+fn large_files_are_edited_and_saved() {
+    run(r#"
+config "large_file_size = 1K"
+write-numbered big.log 500
+open big.log
+wait-loaded
+key Ctrl-F; type "line 250"; key Enter
+type "x"
+expect-line 250 "xline 250"
+expect-status-contains "(modified)"
+key End; key Enter; type "new"
+expect-line-count 501
+expect-line 251 "new"
+key Down; key Home; key Delete
+expect-line 252 "ine 251"
+key Ctrl-S
+expect-status-lacks "(modified)"
+expect-file-line 1 "line 1"
+expect-file-line 2 "line 2"
+expect-file-line 250 "xline 250"
+expect-file-line 251 "new"
+expect-file-line 252 "ine 251"
+expect-file-line 253 "line 252"
+expect-file-line 501 "line 500"
+key Ctrl-F; type "line 500"; key Enter
+key End; key Backspace
+key Ctrl-S
+expect-file-line 501 "line 50"
+expect-line 250 "xline 250"
+expect-line-count 501
+"#);
+}
+
+#[test]
+fn large_files_are_searched_backward_from_the_caret() {
+    run(r#"
+config "large_file_size = 1K"
+write-numbered big.log 500
+open big.log
+wait-loaded
+key Ctrl-F; type "line 30"; key Enter
+expect-status-contains "| 30/500"
+key Ctrl-F; type "line 2"; key Up
+expect-status-contains "| 29/500"
+key Up
+expect-status-contains "| 28/500"
+key Down
+expect-status-contains "| 29/500"
+"#);
+}
+
+#[test]
+fn large_files_are_detected_before_indexing() {
+    run(r#"
+config "large_file_size = 8"
+write-bytes legacy.txt "63 61 66 e9 0a 6f 6b 0a"
+open legacy.txt
+wait-loaded
+expect-line 1 "café"
+expect-status-contains "| Latin-1 |"
+expect-text-bytes "63 61 66 e9 0a 6f 6b 0a"
+write-bytes bin.dat "48 69 00 ff 00 00 00 00 00 00 00 00 00 00 00 00 41"
+open bin.dat
+expect-status-contains "Hex | 00000000/00000011"
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,636 @@
+// Scripted UI tests: a script drives an `Editor` rendering into a
+// `MemoryBackend` and asserts on the buffer, the screen and the disk.
+//
+// Commands are separated by newlines or `;`, arguments by whitespace.
+// Arguments containing whitespace are double quoted; inside quotes `\n`,
+// `\r`, `\t`, `\"`, `\\` and `\u{...}` are unescaped. `$SCRATCH` stands for the
+// scratch directory. Lines, rows and columns are 1-based.
+//
+//     size 80 24                   resize the screen (the default is 80x24)
+//     config "backup = on"         apply configuration settings
+//     open test-graphemes-1.txt    open a scratch copy of a repository file,
+//                                  or a file created with write-file, and
+//                                  switch to it
+//     open a.txt 2 3               open a file with the caret on line 2,
+//                                  column 3
+//     open-text "abc"              open text that does not come from a file
+//     open-bytes "61 0a"           open (hex) bytes that do not come from a
+//                                  file
+//     write-file a/b.txt "abc"     create a file in the scratch directory
+//     write-bytes b.bin "00 ff"    create a file from (hex) bytes
+//     write-numbered big.log 500   create a file of the lines "line 1" to
+//                                  "line 500"
+//     symlink link.txt b.txt       create a symlink in the scratch directory
+//     chmod b.txt 640              set the (octal) permissions of a scratch file
+//     type "abc"                   type text; "\n" presses Enter
+//     key Ctrl-S                   press a key, e.g. Enter, Esc, Left, PageDown
+//     click 1 4                    left click on screen row 1, column 4
+//     drag 2 7                     drag the left button to row 2, column 7
+//     wheel down                   turn the mouse wheel up or down one notch
+//     focus                        the terminal regains focus
+//     blur                         the terminal loses focus
+//     idle 5                       no input arrives for the given seconds
+//     wait-loaded                  wait until a large file is indexed, then
+//                                  redraw
+//     pipe-mode                    act as with --pipe: quitting does not ask
+//                                  about unsaved changes
+//     expect-line 1 "abc"          contents of a buffer line
+//     expect-line-count 3          number of lines in the buffer
+//     expect-text "abc\n"          the document as --pipe writes it, read as
+//                                  UTF-8
+//     expect-text-bytes "61 0a"    the document as --pipe writes it, in (hex)
+//                                  bytes
+//     expect-file "abc\n"          contents of the opened copy on disk
+//     expect-file-line 1 "abc"     a single line of the opened copy on disk
+//     expect-bytes "61 0a"         (hex) bytes of the opened copy on disk
+//     expect-file-at b.txt "abc"   contents of a file in the scratch directory
+//     expect-mode b.txt 640        (octal) permissions of a scratch file
+//     expect-dir "a.txt b.txt"     sorted names in the scratch directory
+//     expect-dir-len backups 2     number of entries in a scratch directory
+//     expect-row 1 "abc"           screen row, ignoring trailing blanks
+//     expect-status-contains "1/3" the status bar shows the given text
+//     expect-status-lacks "[RO]"   the status bar does not show the given text
+//     expect-title "a.txt - hecto" terminal title
+//     expect-caret 1 4             screen position of the caret
+//     expect-caret-hidden          the caret is not shown
+//     expect-quit                  the editor is about to quit
+//     expect-style 1 "abc" string  cells of the first "abc" on screen row 1
+//                                  are drawn with the given theme element
+//     expect-row-style 2 gutter    the whole screen row is drawn with the given
+//                                  theme element
+mod display;
+mod encodings;
+mod externalchanges;
+mod files;
+mod highlighting;
+mod indentation;
+mod largefiles;
+mod mouse;
+mod saving;
+mod swapfiles;
+
+use std::{
+    env,
+    fmt::{Debug, Write},
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use super::terminal::{MemoryBackend, Terminal};
+use super::{config::Config, Editor, FileOptions, ThemeElement};
+use crate::prelude::*;
+
+const DEFAULT_SIZE: Size = Size {
+    height: 24,
+    width: 80,
+};
+
+static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub fn run(script: &str) {
+    let mut harness = Harness::new();
+    for (line_idx, line) in script.lines().enumerate() {
+        for command in split_commands(line) {
+            if let Err(err) = harness.execute(command) {
+                panic!(
+                    "script line {}: `{}`: {err}\n{}",
+                    line_idx.saturating_add(1),
+                    command.trim(),
+                    harness.screen_dump()
+                );
+            }
+        }
+    }
+}
+
+struct Harness {
+    editor: Editor,
+    backend: MemoryBackend,
+    scratch_dir: PathBuf,
+    opened: Option<PathBuf>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let scratch_dir = env::temp_dir().join(format!(
+            "hecto-test-{}-{}",
+            process::id(),
+            SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&scratch_dir).expect("Could not create scratch directory");
+
+        let backend = MemoryBackend::new(DEFAULT_SIZE);
+        let editor = Editor::with_backend(backend.clone()).expect("Could not start editor");
+        let mut harness = Self {
+            editor,
+            backend,
+            scratch_dir,
+            opened: None,
+        };
+        harness.resize(DEFAULT_SIZE);
+        harness
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn execute(&mut self, command: &str) -> Result<(), String> {
+        let scratch_dir = self.scratch_dir.to_string_lossy().into_owned();
+        let args: Vec<_> = tokenize(command)?
+            .iter()
+            .map(|arg| arg.replace("$SCRATCH", &scratch_dir))
+            .collect();
+        let Some((name, args)) = args.split_first() else {
+            return Ok(());
+        };
+        match (name.as_str(), args) {
+            ("size", [width, height]) => {
+                self.resize(Size {
+                    height: parse_number(height)?,
+                    width: parse_number(width)?,
+                });
+            }
+            ("config", [source]) => self.editor.apply_config(&Config::parse(source)?),
+            ("open", [path]) => self.open(path, FileOptions::default())?,
+            ("open", [path, line, col]) => {
+                let at = Location {
+                    line_idx: parse_index(line)?,
+                    grapheme_idx: parse_index(col)?,
+                };
+                self.open(path, FileOptions { at, ..FileOptions::default() })?;
+            }
+            ("open-text", [text]) => {
+                self.editor.open_bytes(text.as_bytes().to_vec(), FileOptions::default());
+                self.editor.refresh_screen();
+            }
+            ("open-bytes", [bytes]) => {
+                self.editor.open_bytes(parse_bytes(bytes)?, FileOptions::default());
+                self.editor.refresh_screen();
+            }
+            ("write-file", [path, contents]) => {
+                let path = self.scratch_dir.join(path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+                }
+                fs::write(path, contents).map_err(|err| err.to_string())?;
+            }
+            ("write-numbered", [path, count]) => {
+                let contents = (1..=parse_number(count)?).fold(String::new(), |mut contents, number| {
+                    let _ = writeln!(contents, "line {number}");
+                    contents
+                });
+                fs::write(self.scratch_dir.join(path), contents).map_err(|err| err.to_string())?;
+            }
+            ("write-bytes", [path, bytes]) => {
+                let path = self.scratch_dir.join(path);
+                fs::write(path, parse_bytes(bytes)?).map_err(|err| err.to_string())?;
+            }
+            #[cfg(unix)]
+            ("symlink", [link, target]) => {
+                std::os::unix::fs::symlink(target, self.scratch_dir.join(link))
+                    .map_err(|err| err.to_string())?;
+            }
+            #[cfg(unix)]
+            ("chmod", [path, mode]) => {
+                use std::os::unix::fs::PermissionsExt;
+                let permissions = fs::Permissions::from_mode(parse_mode(mode)?);
+                fs::set_permissions(self.scratch_dir.join(path), permissions)
+                    .map_err(|err| err.to_string())?;
+            }
+            ("type", [text]) => {
+                for character in text.chars() {
+                    let code = if character == '\n' {
+                        KeyCode::Enter
+                    } else {
+                        KeyCode::Char(character)
+                    };
+                    self.press(KeyEvent::new(code, KeyModifiers::NONE));
+                }
+            }
+            ("key", [key]) => self.press(parse_key(key)?),
+            ("click", [row, col]) => {
+                let kind = MouseEventKind::Down(MouseButton::Left);
+                self.mouse(kind, parse_index(row)?, parse_index(col)?);
+            }
+            ("drag", [row, col]) => {
+                let kind = MouseEventKind::Drag(MouseButton::Left);
+                self.mouse(kind, parse_index(row)?, parse_index(col)?);
+            }
+            ("wheel", [direction]) => {
+                let kind = match direction.as_str() {
+                    "up" => MouseEventKind::ScrollUp,
+                    "down" => MouseEventKind::ScrollDown,
+                    _ => return Err(format!("unknown wheel direction '{direction}'")),
+                };
+                self.mouse(kind, 0, 0);
+            }
+            ("focus", []) => self.editor.handle_event(Event::FocusGained),
+            ("pipe-mode", []) => self.editor.set_pipe_mode(true),
+            ("blur", []) => self.editor.handle_event(Event::FocusLost),
+            ("idle", [seconds]) => self.idle(parse_number(seconds)?)?,
+            ("wait-loaded", []) => self.wait_loaded()?,
+            ("expect-line", [line, expected]) => {
+                let line_idx = parse_index(line)?;
+                let actual = self.editor.view.line_text(line_idx);
+                expect_eq(&actual.as_deref(), &Some(expected.as_str()))?;
+            }
+            ("expect-text", [expected]) => {
+                let mut text = Vec::new();
+                self.editor.view.write_text(&mut text).map_err(|err| err.to_string())?;
+                expect_eq(String::from_utf8_lossy(&text).as_ref(), expected.as_str())?;
+            }
+            ("expect-text-bytes", [expected]) => {
+                let mut text = Vec::new();
+                self.editor.view.write_text(&mut text).map_err(|err| err.to_string())?;
+                expect_eq(text.as_slice(), parse_bytes(expected)?.as_slice())?;
+            }
+            ("expect-line-count", [count]) => {
+                let actual = self.editor.view.get_status().total_lines;
+                expect_eq(&actual, &parse_number(count)?)?;
+            }
+            ("expect-file", [expected]) => {
+                let path = self.opened.as_ref().ok_or("no file has been opened")?;
+                let actual = fs::read_to_string(path).map_err(|err| err.to_string())?;
+                expect_eq(actual.as_str(), expected.as_str())?;
+            }
+            ("expect-bytes", [expected]) => {
+                let path = self.opened.as_ref().ok_or("no file has been opened")?;
+                let actual = fs::read(path).map_err(|err| err.to_string())?;
+                expect_eq(actual.as_slice(), parse_bytes(expected)?.as_slice())?;
+            }
+            ("expect-file-line", [line, expected]) => {
+                let path = self.opened.as_ref().ok_or("no file has been opened")?;
+                let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+                let actual = contents.lines().nth(parse_index(line)?);
+                expect_eq(&actual, &Some(expected.as_str()))?;
+            }
+            ("expect-file-at", [path, expected]) => {
+                let actual = fs::read_to_string(self.scratch_dir.join(path))
+                    .map_err(|err| err.to_string())?;
+                expect_eq(actual.as_str(), expected.as_str())?;
+            }
+            #[cfg(unix)]
+            ("expect-mode", [path, mode]) => {
+                use std::os::unix::fs::PermissionsExt;
+                let metadata = fs::symlink_metadata(self.scratch_dir.join(path))
+                    .map_err(|err| err.to_string())?;
+                let actual = metadata.permissions().mode() & 0o7777;
+                expect_eq(&format!("{actual:o}"), &format!("{:o}", parse_mode(mode)?))?;
+            }
+            ("expect-dir", [expected]) => {
+                let mut names = fs::read_dir(&self.scratch_dir)
+                    .map_err(|err| err.to_string())?
+                    .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| err.to_string())?;
+                names.sort();
+                expect_eq(names.join(" ").as_str(), expected.as_str())?;
+            }
+            ("expect-dir-len", [dir, len]) => {
+                let actual = fs::read_dir(self.scratch_dir.join(dir))
+                    .map_err(|err| err.to_string())?
+                    .count();
+                expect_eq(&actual, &parse_number(len)?)?;
+            }
+            ("expect-row", [row, expected]) => {
+                let actual = self.backend.row_text(parse_index(row)?);
+                expect_eq(actual.trim_end(), expected.as_str())?;
+            }
+            ("expect-status-contains", [expected]) => self.expect_status(expected, true)?,
+            ("expect-status-lacks", [expected]) => self.expect_status(expected, false)?,
+            ("expect-caret", [row, col]) => {
+                let actual = self
+                    .backend
+                    .caret()
+                    .map(|caret| (caret.row.saturating_add(1), caret.col.saturating_add(1)));
+                expect_eq(&actual, &Some((parse_number(row)?, parse_number(col)?)))?;
+            }
+            ("expect-quit", []) => expect_eq(&self.editor.should_quit(), &true)?,
+            ("expect-caret-hidden", []) => expect_eq(&self.backend.caret().is_some(), &false)?,
+            ("expect-title", [expected]) => {
+                expect_eq(self.backend.title().as_str(), expected.as_str())?;
+            }
+            ("expect-style", [row, needle, element]) => {
+                self.expect_style(parse_index(row)?, needle, element)?;
+            }
+            ("expect-row-style", [row, element]) => {
+                self.expect_row_style(parse_index(row)?, element)?;
+            }
+            _ => return Err("unknown command or wrong number of arguments".to_string()),
+        }
+        Ok(())
+    }
+
+    fn expect_status(&self, needle: &str, shown: bool) -> Result<(), String> {
+        let row = self.editor.terminal_size.height.saturating_sub(2);
+        let actual = self.backend.row_text(row);
+        if actual.contains(needle) == shown {
+            Ok(())
+        } else {
+            let expected = if shown { "to contain" } else { "not to contain" };
+            Err(format!("expected the status bar {expected} {needle:?}, found {actual:?}"))
+        }
+    }
+
+    fn resize(&mut self, size: Size) {
+        self.backend.set_size(size);
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        self.editor
+            .handle_event(Event::Resize(size.width as u16, size.height as u16));
+    }
+
+    fn press(&mut self, key_event: KeyEvent) {
+        self.editor.handle_event(Event::Key(key_event));
+    }
+
+    fn mouse(&mut self, kind: MouseEventKind, row: RowIdx, col: ColIdx) {
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        self.editor.handle_event(Event::Mouse(MouseEvent {
+            kind,
+            column: col as u16,
+            row: row as u16,
+            modifiers: KeyModifiers::NONE,
+        }));
+    }
+
+    fn idle(&mut self, seconds: usize) -> Result<(), String> {
+        let seconds = u64::try_from(seconds).map_err(|err| err.to_string())?;
+        let since = Instant::now()
+            .checked_sub(Duration::from_secs(seconds))
+            .ok_or("cannot go back that far")?;
+        self.editor.last_input = Some(since);
+        self.editor.swap_written_at = self.editor.swap_written_at.and(Some(since));
+        self.editor.disk_checked_at = self.editor.disk_checked_at.and(Some(since));
+        self.editor.handle_idle();
+        self.editor.refresh_screen();
+        Ok(())
+    }
+
+    /// Waits for a large file to be indexed, then lets the editor catch up.
+    fn wait_loaded(&mut self) -> Result<(), String> {
+        let started = Instant::now();
+        while self.editor.view.get_status().loading_progress.is_some() {
+            if started.elapsed() > Duration::from_secs(10) {
+                return Err("the file did not finish loading".to_string());
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        self.editor.handle_idle();
+        self.editor.refresh_screen();
+        Ok(())
+    }
+
+    fn open(&mut self, path: &str, options: FileOptions) -> Result<(), String> {
+        let mut copy = self.scratch_dir.join(path);
+        if !copy.exists() {
+            let source = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+            let file_name = source.file_name().ok_or("expected a file name")?;
+            copy = self.scratch_dir.join(file_name);
+            fs::copy(&source, &copy)
+                .map_err(|err| format!("could not copy {}: {err}", source.display()))?;
+        }
+
+        self.editor.open_with(&copy.to_string_lossy(), options);
+        // Files opened after the first are added behind it.
+        let file_name = copy.file_name().map(|name| name.to_string_lossy().into_owned());
+        for _ in 1..self.editor.view.buffer_count() {
+            if Some(&self.editor.view.get_status().file_name) == file_name.as_ref() {
+                break;
+            }
+            self.editor.view.switch_buffer(true);
+        }
+        self.editor.refresh_status();
+        self.editor.refresh_screen();
+        self.opened = Some(copy);
+        Ok(())
+    }
+
+    fn expect_style(&self, row: RowIdx, needle: &str, element: &str) -> Result<(), String> {
+        let element = match element {
+            "text" => None,
+            name => Some(
+                ThemeElement::from_name(name)
+                    .ok_or_else(|| format!("unknown theme element '{name}'"))?,
+            ),
+        };
+
+        let cells: Vec<_> = (0..)
+            .map_while(|col| self.backend.cell(Position { col, row }))
+            .collect();
+        let start = (0..cells.len())
+            .find(|&start| {
+                let mut text = String::new();
+                for cell in cells.iter().skip(start) {
+                    if text.len() >= needle.len() {
+                        break;
+                    }
+                    text.push_str(&cell.symbol);
+                }
+                text == needle
+            })
+            .ok_or_else(|| format!("'{needle}' not found on row {}", row.saturating_add(1)))?;
+
+        // The last cell is padding, so it shows the row's base style
+        // (plain text or the caret line).
+        let base = cells.last().map(|cell| cell.attribute).unwrap_or_default();
+        let expected = element.map_or(base, |element| {
+            base.merge(Terminal::themed_attribute(element))
+        });
+
+        let mut covered = 0;
+        for (offset, cell) in cells.iter().enumerate().skip(start) {
+            if covered >= needle.len() {
+                break;
+            }
+            covered = covered.saturating_add(cell.symbol.len());
+            if cell.attribute != expected {
+                return Err(format!(
+                    "column {} ('{}') is drawn as {:?}, expected {expected:?}",
+                    offset.saturating_add(1),
+                    cell.symbol,
+                    cell.attribute
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn expect_row_style(&self, row: RowIdx, element: &str) -> Result<(), String> {
+        let element = ThemeElement::from_name(element)
+            .ok_or_else(|| format!("unknown theme element '{element}'"))?;
+        let expected = Terminal::themed_attribute(ThemeElement::Text)
+            .merge(Terminal::themed_attribute(element));
+        let cells = (0..).map_while(|col| self.backend.cell(Position { col, row }));
+        for (offset, cell) in cells.enumerate() {
+            if cell.attribute != expected {
+                return Err(format!(
+                    "column {} ('{}') is drawn as {:?}, expected {expected:?}",
+                    offset.saturating_add(1),
+                    cell.symbol,
+                    cell.attribute
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn screen_dump(&self) -> String {
+        let mut dump = String::new();
+        for (row, text) in self.backend.text().iter().enumerate() {
+            let _ = writeln!(dump, "{:>3}|{}", row.saturating_add(1), text.trim_end());
+        }
+        dump
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.scratch_dir);
+    }
+}
+
+fn split_commands(line: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (idx, character) in line.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                commands.push(&line[start..idx]);
+                start = idx.saturating_add(1);
+            }
+            _ => {}
+        }
+    }
+    commands.push(&line[start..]);
+    commands
+}
+
+fn tokenize(command: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = command.trim().chars().peekable();
+
+    while let Some(&character) = chars.peek() {
+        if character.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut token = String::new();
+        if character == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => token.push('\n'),
+                        Some('r') => token.push('\r'),
+                        Some('t') => token.push('\t'),
+                        Some(escaped @ ('"' | '\\')) => token.push(escaped),
+                        Some('u') => token.push(parse_unicode_escape(&mut chars)?),
+                        other => return Err(format!("invalid escape '\\{}'", other.unwrap_or(' '))),
+                    },
+                    Some(other) => token.push(other),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        } else {
+            while let Some(&character) = chars.peek() {
+                if character.is_whitespace() {
+                    break;
+                }
+                token.push(character);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn parse_unicode_escape(chars: &mut impl Iterator<Item = char>) -> Result<char, String> {
+    let escape: String = chars.take_while(|character| *character != '}').collect();
+    escape
+        .strip_prefix('{')
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("invalid escape '\\u{escape}}}'"))
+}
+
+fn parse_key(name: &str) -> Result<KeyEvent, String> {
+    if name == "Shift-Tab" {
+        return Ok(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+    }
+    if let Some(key) = name.strip_prefix("Ctrl-") {
+        let mut chars = key.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(character), None) => Ok(KeyEvent::new(
+                KeyCode::Char(character.to_ascii_lowercase()),
+                KeyModifiers::CONTROL,
+            )),
+            _ => Err(format!("unknown key '{name}'")),
+        };
+    }
+
+    let code = match name {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        _ => return Err(format!("unknown key '{name}'")),
+    };
+    Ok(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, found '{value}'"))
+}
+
+fn parse_index(value: &str) -> Result<usize, String> {
+    match parse_number(value)? {
+        0 => Err("lines, rows and columns start at 1".to_string()),
+        number => Ok(number.saturating_sub(1)),
+    }
+}
+
+#[cfg(unix)]
+fn parse_mode(value: &str) -> Result<u32, String> {
+    u32::from_str_radix(value, 8).map_err(|_| format!("expected an octal mode, found '{value}'"))
+}
+
+/// Reads bytes written in hex, e.g. "ff fe 61 00".
+fn parse_bytes(value: &str) -> Result<Vec<u8>, String> {
+    value
+        .split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| format!("expected a hex byte, found '{byte}'")))
+        .collect()
+}
+
+fn expect_eq<T: PartialEq + Debug + ?Sized>(actual: &T, expected: &T) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("expected {expected:?}, found {actual:?}"))
+    }
+}
\ No newline at end of file
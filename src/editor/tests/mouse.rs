@@ -0,0 +1,65 @@
+// Clicking, dragging and scrolling with the mouse.
+use super::run;
+
+#[test]
+fn clicks_place_the_caret() {
+    run(r#"
+size 80 20
+open test-search-highlighting.txt
+click 2 10
+expect-caret 2 10
+type "X"
+expect-line 2 "haystack Xwith needle in the middle"
+click 19 1
+expect-caret 2 11
+click 20 5
+expect-caret 2 11
+click 1 70
+expect-caret 1 36
+click 13 4
+expect-caret 13 3
+"#);
+}
+
+#[test]
+fn dragging_and_double_clicking_select_text() {
+    run(r#"
+size 80 20
+open test-search-highlighting.txt
+click 2 10
+drag 2 16
+expect-caret 2 16
+expect-style 2 "with n" selection
+expect-style 2 "eedle" text
+drag 3 5
+expect-style 2 "with needle in the middle" selection
+expect-style 3 "what" selection
+expect-style 3 " comes" text
+key Right
+expect-style 2 "with" text
+click 2 12; click 2 12
+expect-style 2 "with" selection
+expect-style 2 " needle" text
+expect-caret 2 14
+"#);
+}
+
+#[test]
+fn the_wheel_scrolls_without_moving_the_caret() {
+    run(r#"
+size 80 10
+open test-search-highlighting.txt
+wheel down
+expect-row 1 "invalid n eedle or need le nee"
+expect-caret-hidden
+wheel down; wheel down
+expect-row 1 "overlapping neeneeneeneeeeneeneeneeneeneeneeneeneeneeneeneeneedle"
+wheel up; wheel up; wheel up
+expect-row 1 "needle at the start of the haystack"
+expect-caret 1 1
+wheel down
+key Right
+expect-row 1 "needle at the start of the haystack"
+expect-caret 1 2
+"#);
+}
\ No newline at end of file
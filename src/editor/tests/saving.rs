@@ -0,0 +1,149 @@
+// Writing files: line endings, atomic saves, backups and autosave.
+use super::run;
+
+#[test]
+fn line_endings_and_a_missing_final_newline_are_preserved() {
+    run(r#"
+write-file crlf.txt "one\r\ntwo\r\nthree"
+open crlf.txt
+expect-status-contains "| CRLF |"
+key End; type "!\nfour"
+expect-status-contains "4 lines(modified)"
+expect-status-contains "| CRLF |"
+key Ctrl-S
+expect-file "one!\r\nfour\r\ntwo\r\nthree"
+"#);
+}
+
+#[test]
+fn mixed_line_endings_are_kept_until_converted() {
+    run(r#"
+write-file mixed.txt "a\nb\r\nc\rd\n"
+open mixed.txt
+expect-status-contains "| Mixed |"
+expect-line 3 "c"
+type "x"
+key Ctrl-S
+expect-file "xa\nb\r\nc\rd\n"
+key Ctrl-E; type "crlf"; key Enter
+expect-row 24 "Line endings set to CRLF."
+expect-status-contains "(modified)"
+expect-status-contains "| CRLF |"
+key Ctrl-S
+expect-file "xa\r\nb\r\nc\r\nd\r\n"
+"#);
+}
+
+#[test]
+#[cfg(unix)]
+fn saving_replaces_the_file_atomically_and_keeps_its_permissions() {
+    run(r#"
+write-file script.sh "echo one\n"
+chmod script.sh 2750
+open script.sh
+type "!"
+key Ctrl-S
+expect-row 24 "File saved successfully."
+expect-file "!echo one\n"
+expect-mode script.sh 2750
+expect-dir "script.sh"
+"#);
+}
+
+#[test]
+#[cfg(unix)]
+fn saving_through_a_symlink_writes_its_target() {
+    run(r#"
+write-file target.txt "abc\n"
+symlink link.txt target.txt
+open link.txt
+type "x"
+key Ctrl-S
+expect-file-at target.txt "xabc\n"
+expect-dir "link.txt target.txt"
+key Ctrl-S
+expect-file-at link.txt "xabc\n"
+"#);
+}
+
+#[test]
+fn failed_saves_report_the_reason() {
+    run(r#"
+key Ctrl-S; type "/nonexistent-hecto-dir/a.txt"; key Enter
+expect-row 24 "Error writing file: entity not found."
+"#);
+}
+
+#[test]
+fn saving_keeps_the_previous_version_next_to_the_file() {
+    run(r#"
+config "backup = on"
+write-file a.txt "one\n"
+open a.txt
+type "x"; key Ctrl-S
+expect-file-at a.txt~ "one\n"
+type "y"; key Ctrl-S
+expect-file-at a.txt~ "xone\n"
+expect-file "xyone\n"
+"#);
+}
+
+#[test]
+fn backups_in_a_directory_are_limited_per_file() {
+    run(r#"
+config "backup_dir = $SCRATCH/backups\nbackup_keep = 2"
+write-file a.txt "one\n"
+open a.txt
+type "x"; key Ctrl-S
+expect-dir-len backups 1
+type "x"; key Ctrl-S
+type "x"; key Ctrl-S
+expect-dir-len backups 2
+expect-dir "a.txt backups"
+"#);
+}
+
+#[cfg(unix)]
+#[test]
+fn files_are_not_overwritten_when_the_backup_fails() {
+    run(r#"
+write-file taken "not a directory"
+config "backup_dir = $SCRATCH/taken/backups"
+write-file a.txt "one\n"
+open a.txt
+type "x"; key Ctrl-S
+expect-row 24 "Error writing file: could not back it up (not a directory)."
+expect-file "one\n"
+"#);
+}
+
+#[test]
+fn autosave_saves_after_the_configured_idle_time() {
+    run(r#"
+config "autosave = 5"
+write-file a.txt "one\n"
+open a.txt
+type "x"
+idle 2
+expect-file "one\n"
+idle 5
+expect-file "xone\n"
+expect-row 24 "File autosaved."
+expect-dir "a.txt"
+"#);
+}
+
+#[test]
+fn autosave_saves_when_focus_is_lost() {
+    run(r#"
+write-file a.txt "one\n"
+open a.txt
+type "x"
+blur
+expect-file "one\n"
+config "autosave = 60"
+blur
+expect-file "xone\n"
+expect-status-lacks "(modified)"
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,132 @@
+// Swap files that keep unsaved edits, and recovering from them.
+use super::run;
+
+#[test]
+fn unsaved_edits_are_kept_in_a_swap_file_until_saved() {
+    run(r#"
+write-file a.txt "one\r\ntwo"
+open a.txt
+type "x"
+expect-file-at .a.txt.hecto-swp "xone\r\ntwo"
+expect-dir ".a.txt.hecto-swp a.txt"
+key Ctrl-S
+expect-dir "a.txt"
+"#);
+}
+
+#[cfg(unix)]
+#[test]
+fn swap_files_get_the_permissions_of_the_file() {
+    run(r#"
+write-file a.txt "one\n"
+chmod a.txt 600
+open a.txt
+type "x"
+expect-mode .a.txt.hecto-swp 600
+"#);
+}
+
+#[test]
+fn vim_swap_files_are_left_alone() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.swp "vim"
+open a.txt
+type "x"
+key Ctrl-S
+key Ctrl-Q
+expect-quit
+expect-dir ".a.txt.swp a.txt"
+"#);
+}
+
+#[test]
+fn leftover_swap_files_can_be_diffed_and_recovered() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.hecto-swp "one\ntwo\n"
+open a.txt
+expect-row 24 "Unsaved changes from a crash! r = recover, d = diff, x = discard, Esc = keep"
+type "d"
+expect-row 1 " one"
+expect-row 2 "+two"
+type "r"
+expect-row 24 "Unsaved changes recovered. Save to keep them."
+expect-line 2 "two"
+expect-status-contains "2 lines(modified)"
+key Ctrl-S
+expect-file "one\ntwo\n"
+expect-dir "a.txt"
+"#);
+}
+
+#[test]
+fn leftover_swap_files_can_be_discarded() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.hecto-swp "uno\n"
+open a.txt
+type "x"
+expect-row 24 "Swap file discarded."
+expect-line 1 "one"
+expect-dir "a.txt"
+"#);
+}
+
+#[test]
+fn leftover_swap_files_can_be_kept_for_later() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.hecto-swp "uno\n"
+open a.txt
+type "d"
+key Esc
+expect-row 24 "Swap file kept for later."
+expect-row 2 "~"
+type "x"
+idle 5
+key Ctrl-S
+expect-file "xone\n"
+expect-file-at .a.txt.hecto-swp "uno\n"
+key Ctrl-Q
+expect-quit
+expect-file-at .a.txt.hecto-swp "uno\n"
+"#);
+}
+
+#[test]
+fn quitting_keeps_leftover_swap_files() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.hecto-swp "uno\n"
+open a.txt
+key Ctrl-Q
+expect-quit
+expect-file-at .a.txt.hecto-swp "uno\n"
+"#);
+}
+
+#[test]
+fn swap_files_matching_the_file_are_removed_silently() {
+    run(r#"
+write-file a.txt "one\n"
+write-file .a.txt.hecto-swp "one\n"
+open a.txt
+expect-dir "a.txt"
+type "x"
+expect-line 1 "xone"
+"#);
+}
+
+#[test]
+fn idle_time_catches_up_on_the_swap_file() {
+    run(r#"
+write-file a.txt "one\n"
+open a.txt
+type "x"
+type "y"
+expect-file-at .a.txt.hecto-swp "xone\n"
+idle 5
+expect-file-at .a.txt.hecto-swp "xyone\n"
+"#);
+}
\ No newline at end of file
@@ -0,0 +1,162 @@
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use crossterm::style::Color;
+
+use super::config::config_dir;
+use super::terminal::{Attribute, Underline};
+
+mod themeelement;
+pub use themeelement::ThemeElement;
+
+pub const DEFAULT_THEME: &str = "dark";
+
+const BUNDLED_THEMES: [(&str, &str); 3] = [
+    ("dark", include_str!("themes/dark.theme")),
+    ("light", include_str!("themes/light.theme")),
+    ("solarized", include_str!("themes/solarized.theme")),
+];
+
+pub struct Theme {
+    name: String,
+    styles: HashMap<ThemeElement, Attribute>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::load(DEFAULT_THEME).unwrap_or_else(|err| {
+            #[cfg(debug_assertions)]
+            {
+                panic!("Bundled theme is malformed: {err}");
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                let _ = err;
+                Self {
+                    name: DEFAULT_THEME.to_string(),
+                    styles: HashMap::new(),
+                }
+            }
+        })
+    }
+}
+
+impl Theme {
+    pub fn bundled_names() -> Vec<&'static str> {
+        BUNDLED_THEMES.iter().map(|(name, _)| *name).collect()
+    }
+
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some((name, source)) = BUNDLED_THEMES.iter().find(|(bundled, _)| *bundled == name) {
+            return Self::parse(name, source);
+        }
+
+        let is_path = name.contains('/')
+            || Path::new(name)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("theme"));
+        let path = if is_path {
+            PathBuf::from(name)
+        } else {
+            config_dir()
+                .map(|dir| dir.join("themes"))
+                .ok_or_else(|| format!("Unknown theme: {name}"))?
+                .join(format!("{name}.theme"))
+        };
+
+        let source = read_to_string(&path)
+            .map_err(|err| format!("Could not read theme {}: {err}", path.display()))?;
+        Self::parse(name, &source)
+    }
+
+    pub fn parse(name: &str, source: &str) -> Result<Self, String> {
+        let mut styles = HashMap::new();
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let line_number = line_idx.saturating_add(1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, spec) = line
+                .split_once('=')
+                .ok_or_else(|| format!("{name}:{line_number}: expected 'element = style'"))?;
+            let key = key.trim();
+            let element = ThemeElement::from_name(key)
+                .ok_or_else(|| format!("{name}:{line_number}: unknown element '{key}'"))?;
+            let attribute =
+                parse_attribute(spec).map_err(|err| format!("{name}:{line_number}: {err}"))?;
+            styles.insert(element, attribute);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            styles,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attribute(&self, element: ThemeElement) -> Attribute {
+        self.styles.get(&element).copied().unwrap_or_default()
+    }
+}
+
+fn parse_attribute(spec: &str) -> Result<Attribute, String> {
+    let mut attribute = Attribute::default();
+    let mut words = spec.split_whitespace();
+
+    while let Some(word) = words.next() {
+        if let Some(underline) = Underline::from_name(word) {
+            attribute.underline = Some(underline);
+            continue;
+        }
+        match word {
+            "bold" => attribute.bold = true,
+            "italic" => attribute.italic = true,
+            "dim" => attribute.dim = true,
+            "reverse" => attribute.reverse = true,
+            "on" => {
+                let color = words
+                    .next()
+                    .ok_or_else(|| "expected a colour after 'on'".to_string())?;
+                attribute.background = parse_color(color)?;
+            }
+            "underline_color" => {
+                let color = words
+                    .next()
+                    .ok_or_else(|| "expected a colour after 'underline_color'".to_string())?;
+                attribute.underline_color = parse_color(color)?;
+            }
+            _ => attribute.foreground = parse_color(word)?,
+        }
+    }
+    Ok(attribute)
+}
+
+fn parse_color(word: &str) -> Result<Option<Color>, String> {
+    if word == "default" {
+        return Ok(None);
+    }
+
+    if let Some(hex) = word.strip_prefix('#') {
+        let channel = |range| {
+            hex.get(range)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+        return match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Some(Color::Rgb { r, g, b })),
+            _ => Err(format!("invalid colour '{word}'")),
+        };
+    }
+
+    Color::try_from(word)
+        .map(Some)
+        .map_err(|()| format!("unknown colour '{word}'"))
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use super::super::AnnotationType;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ThemeElement {
+    Text,
+    StatusBar,
+    Gutter,
+    Selection,
+    CaretLine,
+    Annotation(AnnotationType),
+}
+
+impl ThemeElement {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let element = match name {
+            "text" => Self::Text,
+            "status_bar" => Self::StatusBar,
+            "gutter" => Self::Gutter,
+            "selection" => Self::Selection,
+            "caret_line" => Self::CaretLine,
+            "match" => Self::Annotation(AnnotationType::Match),
+            "selected_match" => Self::Annotation(AnnotationType::SelectedMatch),
+            "number" => Self::Annotation(AnnotationType::Number),
+            "keyword" => Self::Annotation(AnnotationType::Keyword),
+            "type" => Self::Annotation(AnnotationType::Type),
+            "known_value" => Self::Annotation(AnnotationType::KnownValue),
+            "char" => Self::Annotation(AnnotationType::Char),
+            "lifetime_specifier" => Self::Annotation(AnnotationType::LifetimeSpecifier),
+            "comment" => Self::Annotation(AnnotationType::Comment),
+            "string" => Self::Annotation(AnnotationType::String),
+            "key" => Self::Annotation(AnnotationType::Key),
+            "error" => Self::Annotation(AnnotationType::Error),
+            _ => return None,
+        };
+        Some(element)
+    }
+}
+
+impl From<AnnotationType> for ThemeElement {
+    fn from(annotation_type: AnnotationType) -> Self {
+        match annotation_type {
+            AnnotationType::Selection => Self::Selection,
+            annotation_type => Self::Annotation(annotation_type),
+        }
+    }
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::cmp::min;
 
 use crate::prelude::*;
-use super::super::{command::Edit,Line,Size,Terminal};
+use super::super::{command::Edit,indentation::DEFAULT_TAB_WIDTH,Line,Size,Terminal};
 
 use super::UIComponent;
 
@@ -18,7 +18,7 @@ impl CommandBar {
     pub fn handle_edit_command(&mut self,command: Edit){
         match command {
             Edit::Insert(character) => self.value.append_char(character),
-            Edit::Delete | Edit::InsertNewline => {}
+            Edit::Delete | Edit::InsertNewline | Edit::Dedent => {}
             Edit::DeleteBackward => self.value.delete_last(),
         }
         self.set_needs_redraw(true);
@@ -60,12 +60,12 @@ impl UIComponent for CommandBar {
 
     fn draw(&mut self,origin: RowIdx) -> Result<(),std::io::Error> {
         let area_for_value = self.size.width.saturating_sub(self.prompt.len());
-        let value_end = self.value.width();
+        let value_end = self.value.width(DEFAULT_TAB_WIDTH);
         let value_start = value_end.saturating_sub(area_for_value);
         let message = format!(
             "{}{}",
             self.prompt,
-            self.value.get_visible_graphemes(value_start..value_end)
+            self.value.get_visible_graphemes(value_start..value_end, DEFAULT_TAB_WIDTH)
         );
         let to_print = if message.len() <= self.size.width {
             message
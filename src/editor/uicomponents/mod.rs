@@ -6,5 +6,5 @@ mod uicomponent;
 pub use commandbar::CommandBar;
 pub use messagebar::MessageBar;
 pub use statusbar::StatusBar;
-pub use view::View;
+pub use view::{create_syntax_highlight, Buffer, SyntaxHighlighter, View};
 pub use uicomponent::UIComponent;
\ No newline at end of file
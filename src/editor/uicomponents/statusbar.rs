@@ -1,4 +1,4 @@
-use super::super::{DocumentStatus, Size, Terminal};
+use super::super::{DocumentStatus, Size, Terminal, ThemeElement};
 use super::UIComponent;
 use crate::prelude::*;
 
@@ -35,27 +35,36 @@ impl UIComponent for StatusBar {
     fn draw(&mut self,origin_row: RowIdx) -> Result<(),std::io::Error> {
         let line_count = self.current_status.line_count_to_string();
         let modified_indicator = self.current_status.modified_indicator_to_string();
+        let read_only_indicator = self.current_status.read_only_indicator_to_string();
+        let buffer_indicator = self.current_status.buffer_indicator_to_string();
         let beginning = format!(
-            "{} - {line_count}{modified_indicator}",
+            "{}{buffer_indicator}{read_only_indicator} - {line_count}{modified_indicator}",
             self.current_status.file_name
         );
 
         let position_indicator = self.current_status.position_indicator_to_string();
 
         let file_type = self.current_status.file_type_to_string();
-        let back_part = format!("{file_type} | {position_indicator}");
+        let encoding = self.current_status.encoding_to_string();
+        let line_ending = self.current_status.line_ending_to_string();
+        let back_part = if self.current_status.is_binary {
+            format!("Hex | {position_indicator}")
+        } else {
+            format!("{file_type} | {encoding} | {line_ending} | {position_indicator}")
+        };
 
         let remainder_len = self.size.width.saturating_sub(beginning.len());
 
         let status = format!("{beginning}{back_part:>remainder_len$}");
 
-        let to_print = if status.len() <= self.size.width {
-            status
+        let width = self.size.width;
+        let to_print = if status.len() <= width {
+            format!("{status:width$}")
         } else {
-            String::new()
+            " ".repeat(width)
         };
 
-        Terminal::print_inverted_row(origin_row, &to_print)?;
+        Terminal::print_themed_row(origin_row, &to_print, ThemeElement::StatusBar)?;
         Ok(())
     }
 }
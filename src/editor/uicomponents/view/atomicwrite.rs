@@ -0,0 +1,119 @@
+use std::{
+    fs::{self, File, Metadata, OpenOptions},
+    io::{Error, ErrorKind, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+/// Replaces the file at `path` with `contents` without ever leaving it half
+/// written: the contents go to a temporary file in the same directory,
+/// which is flushed to disk and then renamed over the original. Symlinks
+/// are followed, so a link keeps pointing at its target, and the original
+/// file's permissions and, where allowed, ownership are kept.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    write_atomically_with(path, |file| file.write_all(contents))
+}
+
+/// Like `write_atomically`, with the file getting the permissions and,
+/// where allowed, the ownership of `like` instead of keeping its own.
+pub fn write_atomically_like(path: &Path, contents: &[u8], like: &Path) -> Result<(), Error> {
+    let like = fs::metadata(like).ok();
+    write_atomically_as(path, like.as_ref(), |file| file.write_all(contents))
+}
+
+/// Like `write_atomically`, with `write` filling the temporary file, for
+/// contents too big to have in memory at once.
+pub fn write_atomically_with(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let original = fs::metadata(resolve_symlinks(path)).ok();
+    write_atomically_as(path, original.as_ref(), write)
+}
+
+fn write_atomically_as(
+    path: &Path,
+    like: Option<&Metadata>,
+    write: impl FnOnce(&mut File) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let target = resolve_symlinks(path);
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "not a file name"))?;
+    let dir = target
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let temp_path = dir.join(format!(
+        ".{}.{}.hecto-tmp",
+        file_name.to_string_lossy(),
+        process::id()
+    ));
+
+    let result = write_temp_file(&temp_path, write, like)
+        .and_then(|()| fs::rename(&temp_path, &target));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    } else {
+        sync_dir(dir);
+    }
+    result
+}
+
+fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        let Ok(link) = fs::read_link(&path) else {
+            break;
+        };
+        path = match path.parent() {
+            Some(parent) => parent.join(link),
+            None => link,
+        };
+    }
+    path
+}
+
+fn write_temp_file(
+    temp_path: &Path,
+    write: impl FnOnce(&mut File) -> Result<(), Error>,
+    like: Option<&Metadata>,
+) -> Result<(), Error> {
+    let _ = fs::remove_file(temp_path);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    // The contents are never readable by more people than the finished
+    // file's.
+    #[cfg(unix)]
+    if let Some(like) = like {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(like.permissions().mode() & 0o777);
+    }
+    let mut file = options.open(temp_path)?;
+    write(&mut file)?;
+
+    if let Some(original) = like {
+        // Changing the owner clears the setuid and setgid bits, so the
+        // permissions come second.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{fchown, MetadataExt};
+            let _ = fchown(&file, Some(original.uid()), Some(original.gid()));
+        }
+        file.set_permissions(original.permissions())?;
+    }
+    file.sync_all()
+}
+
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    {
+        let _ = File::open(dir).and_then(|dir| dir.sync_all());
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = dir;
+    }
+}
\ No newline at end of file
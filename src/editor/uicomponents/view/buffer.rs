@@ -1,73 +1,597 @@
-use super::super::super::AnnotatedString;
+use super::super::super::{
+    linediff, AnnotatedString, Backup, EditorConfig, Encoding, FileType, Indentation,
+    IndentationOptions, LineEnding,
+};
+use super::atomicwrite::write_atomically;
+use super::diskstate::DiskState;
+use super::hexdump;
+use super::largefile::{LargeFile, SAMPLE_LEN};
+use super::swapfile;
 use super::FileInfo;
-use super::Highlighter;
+use super::{create_syntax_highlight, Highlighter, SyntaxHighlighter};
 use super::Line;
 use crate::prelude::*;
-use std::fs::{read_to_string, File};
-use std::io::Error;
-use std::io::Write;
+use std::fs::{metadata, read, File};
+use std::path::Path;
+use std::io::{Error, ErrorKind, Read, Write};
 use std::ops::Range;
 
+/// Who the swap file next to the file belongs to.
+#[derive(Default, Clone, Copy)]
+enum SwapFileOwner {
+    #[default]
+    Buffer,
+    // Left behind by a crash, and to be kept for a later session.
+    EarlierSession,
+}
+
 #[derive(Default)]
 pub struct Buffer {
-    lines: Vec<Line>,
+    // Each line with the break that ends it.
+    lines: Vec<(Line, LineEnding)>,
+    missing_final_newline: bool,
+    encoding: Encoding,
+    // The raw contents of a binary file, shown as a hex dump instead of
+    // `lines`.
+    bytes: Option<Vec<u8>>,
+    // A file too big to hold in `lines`, which is read and saved a piece at
+    // a time instead.
+    large_file: Option<LargeFile>,
     file_info: FileInfo,
+    // Kept between draws, so that only what changed is highlighted again.
+    syntax_highlighter: Option<Box<dyn SyntaxHighlighter>>,
     dirty: bool,
+    indentation_options: IndentationOptions,
+    editor_config: EditorConfig,
+    disk_state: Option<DiskState>,
+    swap_file_owner: SwapFileOwner,
+    // What was on disk when the user chose to keep their version over it.
+    dismissed_disk_state: Option<DiskState>,
+    backup: Backup,
+    read_only: bool,
 }
 
 impl Buffer {
+    #[must_use]
     pub const fn is_dirty(&self) -> bool {
         self.dirty
     }
 
+    #[must_use]
+    pub const fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    #[must_use]
     pub const fn get_file_info(&self) -> &FileInfo {
         &self.file_info
     }
 
+    #[must_use]
+    pub fn indentation(&self) -> Indentation {
+        self.editor_config
+            .indentation
+            .or(self.indentation_options)
+            .resolve(self.file_info.get_file_type())
+    }
+
+    pub fn set_indentation_options(&mut self, options: IndentationOptions) {
+        self.indentation_options = options;
+    }
+
+    pub fn set_backup(&mut self, backup: Backup) {
+        self.backup = backup;
+    }
+
+    /// The line break used throughout the buffer, or `None` if it mixes
+    /// several.
+    #[must_use]
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        let mut line_endings = self.line_endings();
+        match line_endings.next() {
+            Some(first) => line_endings.all(|other| other == first).then_some(first),
+            None => Some(self.new_line_ending()),
+        }
+    }
+
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        if self.line_endings().any(|current| current != line_ending) {
+            self.dirty = true;
+        }
+        self.fill_line_endings(line_ending);
+        // An explicit conversion wins over `.editorconfig` until reload.
+        self.editor_config.end_of_line = self.editor_config.end_of_line.map(|_| line_ending);
+    }
+
+    /// The break given to new lines: the one `.editorconfig` asks for,
+    /// otherwise the most common one in the buffer.
+    fn new_line_ending(&self) -> LineEnding {
+        self.editor_config.end_of_line.unwrap_or_else(|| {
+            [LineEnding::Cr, LineEnding::CrLf, LineEnding::Lf]
+                .into_iter()
+                .max_by_key(|candidate| {
+                    self.line_endings()
+                        .filter(|line_ending| line_ending == candidate)
+                        .count()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn line_endings(&self) -> impl Iterator<Item = LineEnding> + '_ {
+        self.lines.iter().map(|(_, line_ending)| *line_ending)
+    }
+
+    fn fill_line_endings(&mut self, line_ending: LineEnding) {
+        for (_, current) in &mut self.lines {
+            *current = line_ending;
+        }
+    }
+
+    #[must_use]
+    pub const fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Converts the buffer to `encoding`, which takes effect on save.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        if self.encoding != encoding {
+            self.dirty = true;
+        }
+        self.encoding = encoding;
+        // An explicit conversion wins over `.editorconfig` until reload.
+        self.editor_config.charset = None;
+    }
+
+    /// The encoding a save writes, the one `.editorconfig` asks for if any.
+    fn encoding_for(&self, editor_config: &EditorConfig) -> Encoding {
+        editor_config
+            .charset
+            .as_deref()
+            .and_then(Encoding::from_name)
+            .unwrap_or(self.encoding)
+    }
+
+    #[must_use]
+    pub const fn is_binary(&self) -> bool {
+        self.bytes.is_some()
+    }
+
+    #[must_use]
+    pub fn byte_count(&self) -> usize {
+        self.bytes.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Row `row_idx` of the hex dump of a binary file.
+    #[must_use]
+    pub fn hex_row(&self, row_idx: usize) -> Option<String> {
+        self.bytes
+            .as_deref()
+            .and_then(|bytes| hexdump::format_row(bytes, row_idx))
+    }
+
+    /// Replaces the high or low half of the byte at `idx` of a binary file
+    /// with `nibble`.
+    pub fn overwrite_nibble(&mut self, idx: ByteIdx, nibble: u8, high: bool) {
+        let Some(byte) = self.bytes.as_mut().and_then(|bytes| bytes.get_mut(idx)) else {
+            return;
+        };
+        let nibble = nibble & 0x0f;
+        let new_byte = if high {
+            (nibble << 4) | (*byte & 0x0f)
+        } else {
+            (*byte & 0xf0) | nibble
+        };
+        if new_byte != *byte {
+            *byte = new_byte;
+            self.dirty = true;
+        }
+    }
+
+    #[must_use]
+    pub const fn is_large_file(&self) -> bool {
+        self.large_file.is_some()
+    }
+
+    /// How far indexing a large file has got, in percent, while it is still
+    /// going on.
+    #[must_use]
+    pub fn loading_progress(&self) -> Option<u8> {
+        self.large_file.as_ref().and_then(LargeFile::progress)
+    }
+
+    /// Reads the lines in `range` of a large file from disk, if they are not
+    /// at hand already.
+    pub fn load_lines(&mut self, range: Range<LineIdx>) {
+        if let Some(large_file) = &mut self.large_file {
+            large_file.load(range);
+        }
+    }
+
+    /// Whether unsaved changes are kept in a swap file. It holds text, and
+    /// only what can be edited, and must not replace one left by a crash.
+    const fn keeps_swap_file(&self) -> bool {
+        !self.is_binary()
+            && !self.is_large_file()
+            && matches!(self.swap_file_owner, SwapFileOwner::Buffer)
+    }
+
+    /// A buffer holding `text` that is not backed by a file.
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut buffer = Self::default();
+        buffer.set_contents(text);
+        buffer
+    }
+
+    /// A buffer holding `bytes` that are not backed by a file, such as piped
+    /// input, with the encoding and file type detected as for a file.
+    #[must_use]
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self::default().with_bytes(bytes)
+    }
+
+    /// A buffer holding `lines` that is not backed by a file.
+    #[must_use]
+    pub fn from_lines(lines: &[String]) -> Self {
+        Self {
+            lines: lines
+                .iter()
+                .map(|line| (Line::from(line), LineEnding::default()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn line(&self, idx: LineIdx) -> Option<&Line> {
+        match &self.large_file {
+            Some(large_file) => large_file.line(idx),
+            None => self.lines.get(idx).map(|(line, _)| line),
+        }
+    }
+
+    fn line_mut(&mut self, idx: LineIdx) -> Option<&mut Line> {
+        self.entry_mut(idx).map(|(line, _)| line)
+    }
+
+    /// The line at `idx` with the break that ends it.
+    fn entry_mut(&mut self, idx: LineIdx) -> Option<&mut (Line, LineEnding)> {
+        match &mut self.large_file {
+            Some(large_file) => large_file.line_mut(idx),
+            None => self.lines.get_mut(idx),
+        }
+    }
+
+    fn insert_line(&mut self, idx: LineIdx, line: Line, line_ending: LineEnding) {
+        match &mut self.large_file {
+            Some(large_file) => large_file.insert(idx, (line, line_ending)),
+            None => self.lines.insert(idx, (line, line_ending)),
+        }
+    }
+
+    fn remove_line(&mut self, idx: LineIdx) -> Option<(Line, LineEnding)> {
+        match &mut self.large_file {
+            Some(large_file) => large_file.remove(idx),
+            None => (idx < self.lines.len()).then(|| self.lines.remove(idx)),
+        }
+    }
+
     pub fn grapheme_count(&self, idx: LineIdx) -> GraphemeIdx {
-        self.lines.get(idx).map_or(0, Line::grapheme_count)
+        self.line(idx).map_or(0, Line::grapheme_count)
     }
 
+    #[must_use]
     pub fn width_until(&self,idx: LineIdx,until: GraphemeIdx) -> GraphemeIdx {
-        self.lines.get(idx).map_or(0, |line| line.width_until(until))
+        let tab_width = self.indentation().tab_width;
+        self.line(idx).map_or(0, |line| line.width_until(until, tab_width))
+    }
+
+    #[must_use]
+    pub fn grapheme_idx_at(&self, idx: LineIdx, col: ColIdx) -> GraphemeIdx {
+        let tab_width = self.indentation().tab_width;
+        self.line(idx).map_or(0, |line| line.grapheme_idx_at(col, tab_width))
     }
 
+    #[must_use]
+    pub fn word_bounds(&self, at: Location) -> Range<GraphemeIdx> {
+        self.line(at.line_idx).map_or(
+            at.grapheme_idx..at.grapheme_idx,
+            |line| line.word_bounds(at.grapheme_idx),
+        )
+    }
+
+    #[must_use]
     pub fn get_highlighted_substring(
         &self,
         line_idx: LineIdx,
         range: Range<GraphemeIdx>,
         highlighter: &Highlighter,
     ) -> Option<AnnotatedString> {
-        self.lines.get(line_idx).map(|line| {
-            line.get_annotated_visible_substr(range, Some(&highlighter.get_annotations(line_idx)))
+        let tab_width = self.indentation().tab_width;
+        self.line(line_idx).map(|line| {
+            line.get_annotated_visible_substr(
+                range,
+                Some(&highlighter.get_annotations(line_idx)),
+                tab_width,
+            )
         })
     }
 
     pub fn highlight(&self,idx: LineIdx,highlighter: &mut Highlighter) {
-        if let Some(line) = self.lines.get(idx) {
+        if let Some(line) = self.line(idx) {
             highlighter.highlight(idx, line);
         }
     }
 
-    pub fn load(file_name: &str) -> Result<Self, Error> {
-        let contents = read_to_string(file_name)?;
-        let mut lines = Vec::new();
-        for value in contents.lines() {
-            lines.push(Line::from(value));
+    /// Brings syntax highlighting up to `end`, starting from the first line
+    /// that changed since it last got there.
+    pub fn highlight_syntax(&mut self, end: LineIdx) {
+        let Some(syntax_highlighter) = &mut self.syntax_highlighter else {
+            return;
+        };
+        let end = end.min(self.lines.len());
+        for idx in syntax_highlighter.highlighted_len()..end {
+            if let Some((line, _)) = self.lines.get(idx) {
+                syntax_highlighter.highlight(idx, line);
+            }
+        }
+        if end == self.lines.len() {
+            syntax_highlighter.finish();
+        }
+    }
+
+    #[must_use]
+    pub fn syntax_highlighter(&self) -> Option<&dyn SyntaxHighlighter> {
+        self.syntax_highlighter.as_deref()
+    }
+
+    #[must_use]
+    pub fn get_diagnostic(&self, idx: LineIdx) -> Option<&str> {
+        self.syntax_highlighter
+            .as_ref()
+            .and_then(|syntax_highlighter| syntax_highlighter.get_diagnostic(idx))
+    }
+
+    fn mark_changed(&mut self, idx: LineIdx) {
+        self.dirty = true;
+        self.forget_highlighting_from(idx);
+    }
+
+    fn forget_highlighting_from(&mut self, idx: LineIdx) {
+        if let Some(syntax_highlighter) = &mut self.syntax_highlighter {
+            syntax_highlighter.rewind(idx);
         }
-        Ok(Self {
-            lines,
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn load(file_name: &str, large_file_size: u64) -> Result<Self, Error> {
+        let path = Path::new(file_name);
+        if metadata(path)?.len() > large_file_size {
+            if let Some(buffer) = Self::load_large_file(file_name)? {
+                return Ok(buffer);
+            }
+        }
+        let bytes = read(file_name)?;
+        let buffer = Self {
             file_info: FileInfo::from(file_name),
-            dirty: false,
-        })
+            editor_config: EditorConfig::for_path(Path::new(file_name)),
+            disk_state: Some(DiskState::new(Path::new(file_name), &bytes)),
+            read_only: !is_writable(Path::new(file_name)),
+            ..Self::default()
+        };
+        Ok(buffer.with_bytes(bytes))
     }
 
+    /// Decodes `bytes` into the buffer's lines and detects their file type,
+    /// or keeps them as they are for the hex view if they are binary.
+    fn with_bytes(mut self, bytes: Vec<u8>) -> Self {
+        self.encoding = Encoding::detect(&bytes);
+        // UTF-16 is full of NUL bytes, so only what was not recognised as
+        // such can be binary.
+        if matches!(self.encoding, Encoding::Utf8 | Encoding::Latin1) && hexdump::is_binary(&bytes) {
+            self.bytes = Some(bytes);
+            return self;
+        }
+        let contents = self.encoding.decode(&bytes);
+        let file_type = FileType::detect(self.file_info.get_path(), &contents);
+        self.set_file_type(file_type);
+        self.set_contents(&contents);
+        self
+    }
+
+    /// Opens `file_name` as a large file if, judging by its start, it is
+    /// text that can be split at line feeds. UTF-16 and binary files are
+    /// read whole instead.
+    fn load_large_file(file_name: &str) -> Result<Option<Self>, Error> {
+        let path = Path::new(file_name);
+        let mut sample = Vec::new();
+        File::open(path)?.take(SAMPLE_LEN).read_to_end(&mut sample)?;
+        let encoding = Encoding::detect_prefix(&sample);
+        let is_text = match encoding {
+            Encoding::Utf8 | Encoding::Latin1 => !hexdump::is_binary(&sample),
+            Encoding::Utf8Bom => true,
+            Encoding::Utf16Le | Encoding::Utf16Be => false,
+        };
+        if !is_text {
+            return Ok(None);
+        }
+        let mut file_info = FileInfo::from(file_name);
+        // Highlighting would have to go through the whole file.
+        file_info.set_file_type(FileType::Text);
+        Ok(Some(Self {
+            large_file: Some(LargeFile::open(path, encoding)?),
+            encoding,
+            file_info,
+            read_only: !is_writable(path),
+            ..Self::default()
+        }))
+    }
+
+    fn set_contents(&mut self, contents: &str) {
+        let (split, has_final_newline) = LineEnding::split(contents);
+        self.lines = split
+            .into_iter()
+            .map(|(value, line_ending)| (Line::from(value), line_ending))
+            .collect();
+        self.missing_final_newline = !has_final_newline;
+        self.forget_highlighting_from(0);
+    }
+
+    /// The buffer's text with each line's own break, as it would be saved
+    /// without any `.editorconfig` adjustments.
+    #[must_use]
+    pub fn contents(&self) -> String {
+        let mut contents = String::new();
+        for (idx, (line, line_ending)) in self.lines.iter().enumerate() {
+            contents.push_str(line);
+            if idx.saturating_add(1) < self.lines.len() || !self.missing_final_newline {
+                contents.push_str(line_ending.as_str());
+            }
+        }
+        contents
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the swap file cannot be written.
+    pub fn write_swap_file(&self) -> Result<(), Error> {
+        if !self.keeps_swap_file() {
+            return Ok(());
+        }
+        match self.file_info.get_path() {
+            Some(path) => swapfile::write(path, &self.contents()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn remove_swap_file(&self) {
+        if matches!(self.swap_file_owner, SwapFileOwner::EarlierSession) {
+            return;
+        }
+        if let Some(path) = self.file_info.get_path() {
+            swapfile::remove(path);
+        }
+    }
+
+    /// Whether an earlier session left unsaved changes to this file behind.
+    /// A leftover swap file that matches the file is simply removed.
+    #[must_use]
+    pub fn has_swap_file(&self) -> bool {
+        let Some(path) = self.file_info.get_path().filter(|_| self.keeps_swap_file()) else {
+            return false;
+        };
+        match swapfile::read(path) {
+            Some(swapped) if swapped == self.contents() => {
+                swapfile::remove(path);
+                false
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Leaves the swap file from a crash alone, to be recovered in a later
+    /// session. Unsaved changes made now are not kept in a swap file.
+    pub fn keep_swap_file(&mut self) {
+        self.swap_file_owner = SwapFileOwner::EarlierSession;
+    }
+
+    /// Replaces the text with the unsaved changes kept in the swap file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the swap file cannot be read.
+    pub fn recover_from_swap_file(&mut self) -> Result<(), Error> {
+        let contents = self.swap_file_contents()?;
+        self.set_contents(&contents);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// The changes recovering would make, in the form of `diff_with_disk`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the swap file cannot be read.
+    pub fn diff_with_swap_file(&self) -> Result<Vec<String>, Error> {
+        Ok(diff_contents(&self.contents(), &self.swap_file_contents()?))
+    }
+
+    fn swap_file_contents(&self) -> Result<String, Error> {
+        self.file_info
+            .get_path()
+            .and_then(swapfile::read)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "no swap file"))
+    }
+
+    /// Whether the file was changed by someone else since it was loaded or
+    /// last saved.
+    pub fn changed_on_disk(&mut self) -> bool {
+        match (self.file_info.get_path(), &mut self.disk_state) {
+            (Some(path), Some(disk_state)) => disk_state.has_changed(path),
+            _ => false,
+        }
+    }
+
+    /// Whether the file changed on disk other than in a way the user already
+    /// chose to ignore.
+    pub fn has_unseen_disk_change(&mut self) -> bool {
+        if !self.changed_on_disk() {
+            return false;
+        }
+        let Some(path) = self.file_info.get_path() else {
+            return false;
+        };
+        self.dismissed_disk_state
+            .as_mut()
+            .is_none_or(|dismissed| dismissed.has_changed(path))
+    }
+
+    /// Stops asking about what is on disk now. Saving still asks before
+    /// overwriting it.
+    pub fn dismiss_disk_change(&mut self) {
+        let Some(path) = self.file_info.get_path() else {
+            return;
+        };
+        self.dismissed_disk_state = read(path).ok().map(|bytes| DiskState::new(path, &bytes));
+    }
+
+    /// The changes saving would make to the file on disk, one line each,
+    /// marked with `-` for lines only on disk and `+` for lines only in the
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn diff_with_disk(&self) -> Result<Vec<String>, Error> {
+        let Some(path) = self.file_info.get_path() else {
+            return Ok(Vec::new());
+        };
+        let bytes = read(path)?;
+        if let Some(own_bytes) = &self.bytes {
+            return Ok(diff_contents(&hexdump::dump(&bytes), &hexdump::dump(own_bytes)));
+        }
+        let on_disk = Encoding::detect(&bytes).decode(&bytes);
+        Ok(diff_contents(&on_disk, &self.contents()))
+    }
+
+    #[must_use]
     pub fn search_forward(&self, query: &str, from: Location) -> Option<Location> {
         if query.is_empty() {
             return None;
         }
+        if let Some(large_file) = &self.large_file {
+            return large_file.search_forward(query, from);
+        }
         let mut is_first = true;
-        for (line_idx, line) in self
+        for (line_idx, (line, _)) in self
             .lines
             .iter()
             .enumerate()
@@ -92,14 +616,18 @@ impl Buffer {
         None
     }
 
+    #[must_use]
     pub fn search_backward(&self, query: &str, from: Location) -> Option<Location> {
         if query.is_empty() {
             return None;
         }
+        if let Some(large_file) = &self.large_file {
+            return large_file.search_backward(query, from);
+        }
 
         let mut is_first = true;
 
-        for (line_idx, line) in self
+        for (line_idx, (line, _)) in self
             .lines
             .iter()
             .enumerate()
@@ -131,85 +659,371 @@ impl Buffer {
         None
     }
 
-    fn save_to_file(&self, file_info: &FileInfo) -> Result<(), Error> {
+    fn save_to_file(
+        &self,
+        file_info: &FileInfo,
+        editor_config: &EditorConfig,
+    ) -> Result<Option<DiskState>, Error> {
         if let Some(file_path) = &file_info.get_path() {
-            let mut file = File::create(file_path)?;
-            for line in &self.lines {
-                writeln!(file, "{line}")?;
+            let trim = editor_config.trim_trailing_whitespace == Some(true);
+            let final_newline = editor_config
+                .insert_final_newline
+                .unwrap_or(!self.missing_final_newline);
+
+            let mut contents = String::new();
+            for (idx, (line, line_ending)) in self.lines.iter().enumerate() {
+                contents.push_str(if trim { line.trim_end() } else { line });
+                if idx.saturating_add(1) < self.lines.len() || final_newline {
+                    let line_ending = editor_config.end_of_line.unwrap_or(*line_ending);
+                    contents.push_str(line_ending.as_str());
+                }
             }
-        } else {
-            #[cfg(debug_assertions)]
-            {
-                panic!("Attempting to save with no file path present");
+
+            let bytes = match &self.bytes {
+                Some(bytes) => bytes.clone(),
+                None => self.encoding_for(editor_config).encode(&contents)?,
+            };
+            self.backup.back_up(file_path)?;
+            write_atomically(file_path, &bytes)?;
+            let kept = matches!(self.swap_file_owner, SwapFileOwner::EarlierSession)
+                && self.file_info.get_path() == Some(file_path);
+            if !kept {
+                swapfile::remove(file_path);
             }
+            return Ok(Some(DiskState::new(file_path, &bytes)));
+        }
+        #[cfg(debug_assertions)]
+        {
+            panic!("Attempting to save with no file path present");
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            Ok(None)
         }
-        Ok(())
     }
 
+    /// Brings the remembered line breaks and disk state in line with what
+    /// was just written.
+    fn mark_saved(&mut self, disk_state: Option<DiskState>) {
+        self.disk_state = disk_state;
+        self.dismissed_disk_state = None;
+        if self.is_large_file() {
+            self.dirty = false;
+            return;
+        }
+        self.encoding = self.encoding_for(&self.editor_config);
+        if let Some(line_ending) = self.editor_config.end_of_line {
+            self.fill_line_endings(line_ending);
+        }
+        if let Some(final_newline) = self.editor_config.insert_final_newline {
+            self.missing_final_newline = !final_newline;
+        }
+        if self.editor_config.trim_trailing_whitespace == Some(true) {
+            self.trim_trailing_whitespace();
+        }
+        self.dirty = false;
+    }
+
+    fn trim_trailing_whitespace(&mut self) {
+        let mut first_trimmed = None;
+        for (idx, (line, _)) in self.lines.iter_mut().enumerate() {
+            let trimmed = line.trim_end();
+            if trimmed.len() < line.len() {
+                *line = Line::from(trimmed);
+                first_trimmed = first_trimmed.or(Some(idx));
+            }
+        }
+        if let Some(idx) = first_trimmed {
+            self.forget_highlighting_from(idx);
+        }
+    }
+
+    pub fn set_file_type(&mut self, file_type: FileType) {
+        self.file_info.set_file_type(file_type);
+        self.syntax_highlighter = create_syntax_highlight(file_type);
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
     pub fn save_as(&mut self, file_name: &str) -> Result<(), Error> {
-        let file_info = FileInfo::from(file_name);
-        self.save_to_file(&file_info)?;
+        let mut file_info = FileInfo::from(file_name);
+        if file_info
+            .get_path()
+            .and_then(FileType::from_path)
+            .is_none()
+        {
+            file_info.set_file_type(self.file_info.get_file_type());
+        }
+        let editor_config = EditorConfig::for_path(Path::new(file_name));
+        let disk_state = match &mut self.large_file {
+            Some(large_file) => save_large_file(large_file, &file_info, &self.backup)?,
+            None => self.save_to_file(&file_info, &editor_config)?,
+        };
+        self.remove_swap_file();
+        self.set_file_type(file_info.get_file_type());
         self.file_info = file_info;
-        self.dirty = false;
+        self.swap_file_owner = SwapFileOwner::Buffer;
+        self.read_only = false;
+        self.editor_config = editor_config;
+        self.mark_saved(disk_state);
         Ok(())
     }
 
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
     pub fn save(&mut self) -> Result<(), Error> {
-        self.save_to_file(&self.file_info)?;
-        self.dirty = false;
+        let disk_state = match &mut self.large_file {
+            Some(large_file) => save_large_file(large_file, &self.file_info, &self.backup)?,
+            None => self.save_to_file(&self.file_info, &self.editor_config)?,
+        };
+        self.mark_saved(disk_state);
         Ok(())
     }
 
+    /// Writes the buffer to `out` in its encoding, as saving would without
+    /// `.editorconfig` adjustments, reading a large file through from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text cannot be read or written.
+    pub fn write_contents(&self, out: &mut impl Write) -> Result<(), Error> {
+        if let Some(large_file) = &self.large_file {
+            return large_file.write_to(out, self.encoding);
+        }
+        let bytes = match &self.bytes {
+            Some(bytes) => bytes.clone(),
+            None => self.encoding.encode(&self.contents())?,
+        };
+        out.write_all(&bytes).and_then(|()| out.flush())
+    }
+
+    #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.lines.is_empty()
+        match (&self.bytes, &self.large_file) {
+            (Some(bytes), _) => bytes.is_empty(),
+            (None, Some(_)) => false,
+            (None, None) => self.lines.is_empty(),
+        }
     }
 
+    #[must_use]
     pub const fn is_file_loaded(&self) -> bool {
         self.file_info.has_path()
     }
 
+    #[must_use]
     pub fn height(&self) -> LineIdx {
-        self.lines.len()
+        match (&self.bytes, &self.large_file) {
+            (Some(bytes), _) => hexdump::row_count(bytes.len()),
+            (None, Some(large_file)) => large_file.line_count(),
+            (None, None) => self.lines.len(),
+        }
     }
 
     pub fn insert_char(&mut self, character: char, at: Location) {
         debug_assert!(at.line_idx <= self.height());
 
         if at.line_idx == self.height() {
-            self.lines.push(Line::from(&character.to_string()));
-            self.dirty = true;
-        } else if let Some(line) = self.lines.get_mut(at.line_idx) {
+            let line_ending = self.new_line_ending();
+            self.insert_line(at.line_idx, Line::from(&character.to_string()), line_ending);
+            self.mark_changed(at.line_idx);
+        } else if let Some(line) = self.line_mut(at.line_idx) {
             line.insert_char(character, at.grapheme_idx);
-            self.dirty = true;
+            self.mark_changed(at.line_idx);
         }
     }
 
     pub fn delete(&mut self, at: Location) {
-        if let Some(line) = self.lines.get(at.line_idx) {
-            if at.grapheme_idx >= line.grapheme_count()
-                && self.height() > at.line_idx.saturating_add(1)
-            {
-                let next_line = self.lines.remove(at.line_idx.saturating_add(1));
-
-                #[allow(clippy::indexing_slicing)]
-                self.lines[at.line_idx].append(&next_line);
-                self.dirty = true;
-            } else if at.grapheme_idx < line.grapheme_count() {
-                #[allow(clippy::indexing_slicing)]
-                self.lines[at.line_idx].delete(at.grapheme_idx);
-                self.dirty = true;
+        let Some(grapheme_count) = self.line(at.line_idx).map(Line::grapheme_count) else {
+            return;
+        };
+        if at.grapheme_idx >= grapheme_count && self.height() > at.line_idx.saturating_add(1) {
+            // The joined line ends like the next one did.
+            let Some((next, next_line_ending)) = self.remove_line(at.line_idx.saturating_add(1))
+            else {
+                return;
+            };
+            if let Some((line, line_ending)) = self.entry_mut(at.line_idx) {
+                line.append(&next);
+                *line_ending = next_line_ending;
+            }
+            self.mark_changed(at.line_idx);
+        } else if at.grapheme_idx < grapheme_count {
+            if let Some(line) = self.line_mut(at.line_idx) {
+                line.delete(at.grapheme_idx);
             }
+            self.mark_changed(at.line_idx);
         }
     }
 
-    pub fn insert_newline(&mut self, at: Location) {
+    /// Splits the line at `at`. The new line keeps the indentation of the
+    /// split line, one level deeper if it ends in an opening bracket.
+    /// Returns the number of graphemes in that indentation.
+    pub fn insert_newline(&mut self, at: Location) -> GraphemeIdx {
         if at.line_idx == self.height() {
-            self.lines.push(Line::default());
-            self.dirty = true;
-        } else if let Some(line) = self.lines.get_mut(at.line_idx) {
-            let new = line.split(at.grapheme_idx);
-            self.lines.insert(at.line_idx.saturating_add(1), new);
-            self.dirty = true;
+            let line_ending = self.new_line_ending();
+            self.insert_line(at.line_idx, Line::default(), line_ending);
+            self.mark_changed(at.line_idx);
+            return 0;
+        }
+
+        let file_type = self.file_info.get_file_type();
+        let indentation = self.indentation();
+        let Some((line, line_ending)) = self.entry_mut(at.line_idx) else {
+            return 0;
+        };
+        let line_ending = *line_ending;
+        let remainder = line.split(at.grapheme_idx);
+
+        let base_indent: String = line
+            .chars()
+            .take_while(|character| character.is_whitespace())
+            .collect();
+        let opens_block = line
+            .trim_end()
+            .chars()
+            .last()
+            .is_some_and(|character| file_type.opens_block(character));
+        let remainder = remainder.trim_start();
+        let mut indent = base_indent.clone();
+        if opens_block {
+            indent.push_str(&indentation.unit());
+        }
+
+        // Between a bracket pair, the closing bracket goes on a line of its
+        // own below the caret, at the indentation of the opening one.
+        let new_lines = if opens_block
+            && remainder
+                .chars()
+                .next()
+                .is_some_and(|character| file_type.closes_block(character))
+        {
+            vec![Line::from(&indent), Line::from(&format!("{base_indent}{remainder}"))]
+        } else {
+            vec![Line::from(&format!("{indent}{remainder}"))]
+        };
+        for (offset, new) in (1..).zip(new_lines) {
+            self.insert_line(at.line_idx.saturating_add(offset), new, line_ending);
         }
+        self.mark_changed(at.line_idx);
+        Line::from(&indent).grapheme_count()
     }
+
+    /// Removes one level of indentation in front of `at` if `character`
+    /// closes a block and only whitespace precedes it on the line. Returns
+    /// the number of graphemes removed.
+    pub fn dedent_before_closing(&mut self, character: char, at: Location) -> GraphemeIdx {
+        let indentation = self.indentation();
+        if !self.file_info.get_file_type().closes_block(character) {
+            return 0;
+        }
+        let Some(line) = self.line_mut(at.line_idx) else {
+            return 0;
+        };
+
+        let split_idx = line.grapheme_idx_to_byte_idx(at.grapheme_idx);
+        let (before, after) = line.split_at(split_idx);
+        if before.is_empty() || !before.trim().is_empty() {
+            return 0;
+        }
+        let kept = indentation.dedent(before);
+
+        let dedented = Line::from(&format!("{kept}{after}"));
+        let removed = line.grapheme_count().saturating_sub(dedented.grapheme_count());
+        *line = dedented;
+        self.mark_changed(at.line_idx);
+        removed
+    }
+
+    /// Adds one level of indentation to the start of a non-empty line.
+    /// Returns the number of graphemes inserted.
+    pub fn indent_line(&mut self, idx: LineIdx) -> GraphemeIdx {
+        let unit = self.indentation().unit();
+        let Some(line) = self.line_mut(idx) else {
+            return 0;
+        };
+        if line.is_empty() {
+            return 0;
+        }
+
+        *line = Line::from(&format!("{unit}{line}"));
+        self.mark_changed(idx);
+        Line::from(&unit).grapheme_count()
+    }
+
+    /// Removes one level of indentation from the start of a line. Returns
+    /// the number of graphemes removed.
+    pub fn dedent_line(&mut self, idx: LineIdx) -> GraphemeIdx {
+        let indentation = self.indentation();
+        let Some(line) = self.line_mut(idx) else {
+            return 0;
+        };
+
+        let rest = line.trim_start();
+        let leading = &line[..line.len().saturating_sub(rest.len())];
+        let kept = indentation.dedent(leading);
+        if kept.len() == leading.len() {
+            return 0;
+        }
+
+        let dedented = Line::from(&format!("{kept}{rest}"));
+        let removed = line.grapheme_count().saturating_sub(dedented.grapheme_count());
+        *line = dedented;
+        self.mark_changed(idx);
+        removed
+    }
+}
+
+/// Whether the current user may write to the file at `path`. A file
+/// without any write permission counts as read-only even for root.
+fn is_writable(path: &Path) -> bool {
+    metadata(path).is_ok_and(|metadata| !metadata.permissions().readonly())
+        && may_write(path)
+}
+
+/// Asks the system, which knows about root and ACLs, without opening the
+/// file: even an unchanged file wakes up anyone watching for writes when it
+/// is closed, and opening a FIFO blocks.
+#[cfg(unix)]
+fn may_write(path: &Path) -> bool {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `path` is a NUL-terminated string that outlives the call.
+    unsafe { libc::faccessat(libc::AT_FDCWD, path.as_ptr(), libc::W_OK, libc::AT_EACCESS) == 0 }
 }
+
+#[cfg(not(unix))]
+const fn may_write(_path: &Path) -> bool {
+    true
+}
+
+/// Streams a large file to its path. `.editorconfig` adjustments, which
+/// would need every line in memory, are left out. A large file has no disk
+/// state to compare with.
+fn save_large_file(
+    large_file: &mut LargeFile,
+    file_info: &FileInfo,
+    backup: &Backup,
+) -> Result<Option<DiskState>, Error> {
+    if let Some(path) = file_info.get_path() {
+        backup.back_up(path)?;
+        large_file.save(path)?;
+    }
+    Ok(None)
+}
+
+fn diff_contents(old: &str, new: &str) -> Vec<String> {
+    let (old, _) = LineEnding::split(old);
+    let (new, _) = LineEnding::split(new);
+    let old: Vec<&str> = old.iter().map(|(line, _)| *line).collect();
+    let new: Vec<&str> = new.iter().map(|(line, _)| *line).collect();
+    linediff::diff(&old, &new)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
\ No newline at end of file
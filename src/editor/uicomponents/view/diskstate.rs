@@ -0,0 +1,85 @@
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+    time::SystemTime,
+};
+
+/// What the file looked like on disk when it was last loaded or saved.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DiskState {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl DiskState {
+    /// The state of the file at `path`, which was just read or written as
+    /// `contents`.
+    #[must_use]
+    pub fn new(path: &Path, contents: &[u8]) -> Self {
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        Self {
+            modified,
+            len: contents.len().try_into().unwrap_or(u64::MAX),
+            hash: hash(contents),
+        }
+    }
+
+    /// Whether the file at `path` now holds something else. A changed
+    /// modification time alone, as after `touch`, does not count; the new
+    /// one is remembered, so the file is not read again until it changes
+    /// once more. A file that cannot be read, e.g. because it was deleted,
+    /// counts as unchanged: there is nothing to reload, and saving simply
+    /// recreates it.
+    pub fn has_changed(&mut self, path: &Path) -> bool {
+        let Ok(metadata) = fs::metadata(path) else {
+            return false;
+        };
+        if metadata.len() != self.len {
+            return true;
+        }
+        let modified = metadata.modified().ok();
+        if modified == self.modified {
+            return false;
+        }
+        match fs::read(path) {
+            Ok(contents) if hash(&contents) != self.hash => true,
+            Ok(_) => {
+                self.modified = modified;
+                false
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+fn hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs::File, process, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn touched_files_are_unchanged_and_not_read_again() {
+        let path = env::temp_dir().join(format!("hecto-diskstate-{}", process::id()));
+        fs::write(&path, "abc").expect("Could not write file");
+        let mut disk_state = DiskState::new(&path, b"abc");
+        let touched = SystemTime::now().checked_add(Duration::from_secs(10));
+        if let Some(touched) = touched {
+            let file = File::options().write(true).open(&path).expect("Could not open file");
+            file.set_modified(touched).expect("Could not touch file");
+        }
+        assert!(!disk_state.has_changed(&path));
+        assert_eq!(disk_state.modified, touched);
+        fs::write(&path, "xyz").expect("Could not write file");
+        assert!(disk_state.has_changed(&path));
+        let _ = fs::remove_file(&path);
+    }
+}
\ No newline at end of file
@@ -12,34 +12,35 @@ pub struct FileInfo {
 }
 
 impl FileInfo {
+    #[must_use]
     pub fn from(file_name: &str) -> Self {
         let path = PathBuf::from(file_name);
-        let file_type = if path
-            .extension()
-            .map_or(false, |ext| ext.eq_ignore_ascii_case("rs"))
-        {
-            FileType::Rust
-        } else {
-            FileType::Text
-        };
+        let file_type = FileType::from_path(&path).unwrap_or_default();
         Self {
             path: Some(path),
             file_type
         }
     }
 
+    #[must_use]
     pub fn get_path(&self) -> Option<&Path> {
         self.path.as_deref()
     }
 
+    #[must_use]
     pub const fn has_path(&self) -> bool {
         self.path.is_some()
     }
 
+    #[must_use]
     pub const fn get_file_type(&self) -> FileType {
         self.file_type
     }
 
+    pub fn set_file_type(&mut self, file_type: FileType) {
+        self.file_type = file_type;
+    }
+
 
 }
 
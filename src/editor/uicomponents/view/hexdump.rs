@@ -0,0 +1,131 @@
+use std::fmt::Write;
+
+use crate::prelude::*;
+
+pub const BYTES_PER_ROW: usize = 16;
+// How much of a file is looked at to tell binary data from text.
+const SAMPLE_LEN: usize = 8192;
+// Where the hex digits and the characters of a row start, after an offset
+// like `00000010` and two spaces.
+const HEX_COL: ColIdx = 10;
+const ASCII_COL: ColIdx = 61;
+
+/// Whether `bytes` look like binary data rather than text: they hold a NUL
+/// byte, or more than a third of them are not valid UTF-8.
+#[must_use]
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SAMPLE_LEN)];
+    if sample.contains(&0) {
+        return true;
+    }
+    let invalid: usize = sample
+        .utf8_chunks()
+        .map(|chunk| chunk.invalid().len())
+        .sum();
+    invalid.saturating_mul(3) > sample.len()
+}
+
+#[must_use]
+pub fn row_count(len: usize) -> usize {
+    len.div_ceil(BYTES_PER_ROW)
+}
+
+/// Row `row_idx` of the dump of `bytes`, in the layout of `hexdump -C`:
+/// `00000010  48 65 6c 6c 6f 0a 00 00  ...  |Hello...|`.
+#[must_use]
+pub fn format_row(bytes: &[u8], row_idx: usize) -> Option<String> {
+    let start = row_idx.checked_mul(BYTES_PER_ROW)?;
+    let row = bytes.chunks(BYTES_PER_ROW).nth(row_idx)?;
+    let mut text = format!("{start:08x} ");
+    for idx in 0..BYTES_PER_ROW {
+        if idx == BYTES_PER_ROW.checked_div(2).unwrap_or(0) {
+            text.push(' ');
+        }
+        match row.get(idx) {
+            Some(byte) => {
+                let _ = write!(text, " {byte:02x}");
+            }
+            None => text.push_str("   "),
+        }
+    }
+    text.push_str("  |");
+    text.extend(row.iter().map(|byte| {
+        if byte.is_ascii_graphic() || *byte == b' ' {
+            char::from(*byte)
+        } else {
+            '.'
+        }
+    }));
+    text.push('|');
+    Some(text)
+}
+
+/// The whole dump, one row per line, for showing differences.
+#[must_use]
+pub fn dump(bytes: &[u8]) -> String {
+    (0..row_count(bytes.len()))
+        .filter_map(|row_idx| format_row(bytes, row_idx))
+        .map(|row| row + "\n")
+        .collect()
+}
+
+/// The column of the first hex digit of byte `idx` of a row.
+#[must_use]
+pub fn column_of(idx: usize) -> ColIdx {
+    let gap = usize::from(idx >= BYTES_PER_ROW.checked_div(2).unwrap_or(0));
+    HEX_COL.saturating_add(idx.saturating_mul(3)).saturating_add(gap)
+}
+
+/// The byte of a row shown at column `col`, in either the hex digits or the
+/// characters.
+#[must_use]
+pub fn byte_at_column(col: ColIdx) -> usize {
+    let last = BYTES_PER_ROW.saturating_sub(1);
+    if col >= ASCII_COL {
+        return col.saturating_sub(ASCII_COL).min(last);
+    }
+    (0..BYTES_PER_ROW)
+        .rev()
+        .find(|idx| column_of(*idx) <= col)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_look_like_hexdump() {
+        let bytes = b"Hello, world!\n\0\x01abc";
+        assert_eq!(
+            format_row(bytes, 0).as_deref(),
+            Some("00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |Hello, world!...|")
+        );
+        assert_eq!(
+            format_row(bytes, 1).as_deref(),
+            Some("00000010  61 62 63                                          |abc|")
+        );
+        assert_eq!(format_row(bytes, 2), None);
+        assert_eq!(dump(bytes).lines().count(), row_count(bytes.len()));
+    }
+
+    #[test]
+    fn columns_and_bytes_correspond() {
+        for idx in 0..BYTES_PER_ROW {
+            assert_eq!(byte_at_column(column_of(idx)), idx);
+            assert_eq!(byte_at_column(column_of(idx).saturating_add(1)), idx);
+            assert_eq!(byte_at_column(ASCII_COL.saturating_add(idx)), idx);
+        }
+        assert_eq!(column_of(8), column_of(7).saturating_add(4));
+        assert_eq!(byte_at_column(0), 0);
+    }
+
+    #[test]
+    fn binary_detection() {
+        assert!(!is_binary(b"text\n"));
+        assert!(!is_binary("Grüße".as_bytes()));
+        assert!(!is_binary(b"Gr\xfc\xdfe, mostly text"));
+        assert!(is_binary(b"ab\0cd"));
+        assert!(is_binary(b"\xff\xfe\xfd"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::{collections::HashMap, mem};
+
+use super::{Annotation, AnnotationType};
+use crate::prelude::*;
+
+/// What a syntax highlighter found on the lines it went through, along with
+/// the state it was in at the start of each, so that it can pick up again
+/// from any of them after an edit.
+pub struct Highlights<S> {
+    lines: Vec<Vec<Annotation>>,
+    states: Vec<S>,
+    diagnostics: HashMap<LineIdx, String>,
+    // Errors added by `finish` to earlier lines, with whether the line's
+    // diagnostic came with them, so that `rewind` can take them back.
+    unclosed: Vec<(LineIdx, bool)>,
+    finished: bool,
+}
+
+impl<S> Default for Highlights<S> {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            states: Vec::new(),
+            diagnostics: HashMap::new(),
+            unclosed: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<S: Clone> Highlights<S> {
+    /// The number of lines highlighted so far.
+    pub fn len(&self) -> LineIdx {
+        self.lines.len()
+    }
+
+    /// Adds the annotations of the next line, which was started in `state`.
+    pub fn push(&mut self, state: S, annotations: Vec<Annotation>) {
+        self.states.push(state);
+        self.lines.push(annotations);
+    }
+
+    pub fn get(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.lines.get(idx)
+    }
+
+    /// Keeps `message` for line `idx` unless the line already has one.
+    pub fn report(&mut self, idx: LineIdx, message: String) {
+        self.diagnostics.entry(idx).or_insert(message);
+    }
+
+    pub fn diagnostic(&self, idx: LineIdx) -> Option<&str> {
+        self.diagnostics.get(&idx).map(String::as_str)
+    }
+
+    /// Returns true the first time it is called after the last line was
+    /// highlighted, and false if the end was dealt with already.
+    pub fn finish(&mut self) -> bool {
+        !mem::replace(&mut self.finished, true)
+    }
+
+    /// Marks what is left open at the end of the document at `start` on
+    /// line `idx`.
+    pub fn report_unclosed(&mut self, idx: LineIdx, start: ByteIdx, message: String) {
+        let Some(annotations) = self.lines.get_mut(idx) else {
+            return;
+        };
+        annotations.push(Annotation {
+            annotation_type: AnnotationType::Error,
+            start,
+            end: start.saturating_add(1),
+        });
+        let has_diagnostic = self.diagnostics.contains_key(&idx);
+        self.report(idx, message);
+        self.unclosed.push((idx, !has_diagnostic));
+    }
+
+    /// Forgets line `idx` and everything after it. Returns the state
+    /// highlighting has to resume in, or `None` if it got no further.
+    pub fn rewind(&mut self, idx: LineIdx) -> Option<S> {
+        // The end of the document may have moved.
+        for (line_idx, with_diagnostic) in self.unclosed.drain(..).rev() {
+            if let Some(annotations) = self.lines.get_mut(line_idx) {
+                annotations.pop();
+            }
+            if with_diagnostic {
+                self.diagnostics.remove(&line_idx);
+            }
+        }
+        self.finished = false;
+        if idx >= self.len() {
+            return None;
+        }
+        self.lines.truncate(idx);
+        self.diagnostics.retain(|line_idx, _| *line_idx < idx);
+        let state = self.states.get(idx).cloned();
+        self.states.truncate(idx);
+        state
+    }
+}
\ No newline at end of file
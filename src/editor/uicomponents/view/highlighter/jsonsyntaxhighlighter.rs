@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use super::{
+    highlights::Highlights,
+    scalar::{is_boolean, is_number, quoted_len, token_len},
+    syntaxhighlighter::SyntaxHighlighter,
+    Annotation, AnnotationType, Line,
+};
+use crate::prelude::*;
+
+const DELIMITERS: [char; 6] = [',', ':', '[', ']', '{', '}'];
+
+#[derive(Clone)]
+struct Frame {
+    bracket: char,
+    line_idx: LineIdx,
+    start: ByteIdx,
+    // Where in `JsonSyntaxHighlighter::keys` the keys of this object are.
+    keys: usize,
+}
+
+// What carries over from one line to the next.
+#[derive(Clone, Default)]
+struct State {
+    frames: Vec<Frame>,
+    opened: usize,
+}
+
+#[derive(Default)]
+pub struct JsonSyntaxHighlighter {
+    highlights: Highlights<State>,
+    state: State,
+    // The keys of every object in the order they were opened, each with the
+    // line it first appeared on. They are kept apart from the state so that
+    // remembering the state of each line stays cheap.
+    keys: Vec<HashMap<String, LineIdx>>,
+}
+
+impl JsonSyntaxHighlighter {
+    fn report(&mut self, idx: LineIdx, errors: &mut Vec<Annotation>, start: ByteIdx, end: ByteIdx, message: String) {
+        errors.push(Annotation {
+            annotation_type: AnnotationType::Error,
+            start,
+            end,
+        });
+        self.highlights.report(idx, message);
+    }
+
+    fn annotate_string(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        start: ByteIdx,
+        result: &mut Vec<Annotation>,
+        errors: &mut Vec<Annotation>,
+    ) -> ByteIdx {
+        let Some(len) = quoted_len(&line[start..], true) else {
+            self.report(idx, errors, start, line.len(), "Unterminated string".to_string());
+            return line.len().saturating_sub(start);
+        };
+        let end = start.saturating_add(len);
+        let is_key = line[end..].trim_start().starts_with(':');
+
+        result.push(Annotation {
+            annotation_type: if is_key {
+                AnnotationType::Key
+            } else {
+                AnnotationType::String
+            },
+            start,
+            end,
+        });
+
+        if is_key {
+            let key = &line[start.saturating_add(1)..end.saturating_sub(1)];
+            let keys = self
+                .state
+                .frames
+                .last()
+                .filter(|frame| frame.bracket == '{')
+                .and_then(|frame| self.keys.get_mut(frame.keys));
+            let is_duplicate = keys.is_some_and(|keys| {
+                keys.contains_key(key) || keys.insert(key.to_string(), idx).is_some()
+            });
+            if is_duplicate {
+                self.report(idx, errors, start, end, format!("Duplicate key \"{key}\""));
+            }
+        }
+        len
+    }
+
+    fn close_bracket(&mut self, idx: LineIdx, closing: char, at: ByteIdx, errors: &mut Vec<Annotation>) {
+        let expected = if closing == '}' { '{' } else { '[' };
+        match self.state.frames.pop() {
+            Some(frame) if frame.bracket == expected => {}
+            Some(frame) => self.report(
+                idx,
+                errors,
+                at,
+                at.saturating_add(1),
+                format!(
+                    "Mismatched '{closing}': '{}' from line {} is still open",
+                    frame.bracket,
+                    frame.line_idx.saturating_add(1)
+                ),
+            ),
+            None => self.report(
+                idx,
+                errors,
+                at,
+                at.saturating_add(1),
+                format!("Unexpected '{closing}' without matching '{expected}'"),
+            ),
+        }
+    }
+}
+
+impl SyntaxHighlighter for JsonSyntaxHighlighter {
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        debug_assert_eq!(idx, self.highlights.len());
+        let state = self.state.clone();
+        let mut result = Vec::new();
+        let mut errors = Vec::new();
+        let mut pos = 0;
+
+        while let Some(char) = line[pos..].chars().next() {
+            let len = match char {
+                '"' => self.annotate_string(idx, line, pos, &mut result, &mut errors),
+                '{' | '[' => {
+                    self.state.frames.push(Frame {
+                        bracket: char,
+                        line_idx: idx,
+                        start: pos,
+                        keys: self.keys.len(),
+                    });
+                    self.keys.push(HashMap::new());
+                    self.state.opened = self.keys.len();
+                    1
+                }
+                '}' | ']' => {
+                    self.close_bracket(idx, char, pos, &mut errors);
+                    1
+                }
+                '/' if line[pos..].starts_with("//") => {
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Comment,
+                        start: pos,
+                        end: line.len(),
+                    });
+                    line.len().saturating_sub(pos)
+                }
+                ',' | ':' => 1,
+                _ if char.is_whitespace() => char.len_utf8(),
+                _ => {
+                    let len = token_len(&line[pos..], &DELIMITERS).max(char.len_utf8());
+                    let word = &line[pos..pos.saturating_add(len)];
+                    let end = pos.saturating_add(len);
+                    if is_boolean(word) || word == "null" {
+                        result.push(Annotation {
+                            annotation_type: AnnotationType::KnownValue,
+                            start: pos,
+                            end,
+                        });
+                    } else if is_number(word) {
+                        result.push(Annotation {
+                            annotation_type: AnnotationType::Number,
+                            start: pos,
+                            end,
+                        });
+                    } else {
+                        self.report(idx, &mut errors, pos, end, format!("Unexpected token '{word}'"));
+                    }
+                    len
+                }
+            };
+            pos = pos.saturating_add(len);
+        }
+
+        result.append(&mut errors);
+        self.highlights.push(state, result);
+    }
+
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(idx)
+    }
+
+    fn finish(&mut self) {
+        if !self.highlights.finish() {
+            return;
+        }
+        for frame in &self.state.frames {
+            self.highlights.report_unclosed(
+                frame.line_idx,
+                frame.start,
+                format!("Unclosed '{}'", frame.bracket),
+            );
+        }
+    }
+
+    fn get_diagnostic(&self, idx: LineIdx) -> Option<&str> {
+        self.highlights.diagnostic(idx)
+    }
+
+    fn rewind(&mut self, idx: LineIdx) {
+        let Some(state) = self.highlights.rewind(idx) else {
+            return;
+        };
+        // Objects opened from line `idx` on are gone, and the ones still
+        // open lose the keys found there.
+        self.keys.truncate(state.opened);
+        for frame in &state.frames {
+            if let Some(keys) = self.keys.get_mut(frame.keys) {
+                keys.retain(|_, line_idx| *line_idx < idx);
+            }
+        }
+        self.state = state;
+    }
+
+    fn highlighted_len(&self) -> LineIdx {
+        self.highlights.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(lines: &[&str]) -> JsonSyntaxHighlighter {
+        let mut highlighter = JsonSyntaxHighlighter::default();
+        for (idx, line) in lines.iter().enumerate() {
+            highlighter.highlight(idx, &Line::from(line));
+        }
+        highlighter.finish();
+        highlighter
+    }
+
+    fn annotated<'a>(highlighter: &JsonSyntaxHighlighter, lines: &[&'a str], idx: LineIdx) -> Vec<(AnnotationType, &'a str)> {
+        highlighter
+            .get_annotations(idx)
+            .into_iter()
+            .flatten()
+            .map(|annotation| (annotation.annotation_type, &lines[idx][annotation.start..annotation.end]))
+            .collect()
+    }
+
+    #[test]
+    fn values() {
+        let lines = [r#"{"name": "hecto", "size": -1.5e3, "ok": true, "none": null} // note"#];
+        let highlighter = highlight(&lines);
+        assert_eq!(
+            annotated(&highlighter, &lines, 0),
+            [
+                (AnnotationType::Key, r#""name""#),
+                (AnnotationType::String, r#""hecto""#),
+                (AnnotationType::Key, r#""size""#),
+                (AnnotationType::Number, "-1.5e3"),
+                (AnnotationType::Key, r#""ok""#),
+                (AnnotationType::KnownValue, "true"),
+                (AnnotationType::Key, r#""none""#),
+                (AnnotationType::KnownValue, "null"),
+                (AnnotationType::Comment, "// note"),
+            ]
+        );
+        assert_eq!(highlighter.get_diagnostic(0), None);
+    }
+
+    #[test]
+    fn times_are_not_numbers() {
+        let highlighter = highlight(&[r#"{"at": 07T30Z}"#]);
+        assert_eq!(highlighter.get_diagnostic(0), Some("Unexpected token '07T30Z'"));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let lines = ["{", r#"  "a": 1,"#, r#"  "b": {"a": 2},"#, r#"  "a": 3"#, "}"];
+        let highlighter = highlight(&lines);
+        assert_eq!(highlighter.get_diagnostic(2), None);
+        assert_eq!(highlighter.get_diagnostic(3), Some(r#"Duplicate key "a""#));
+    }
+
+    #[test]
+    fn unbalanced_brackets() {
+        let highlighter = highlight(&["[", "  {]", "]", "}"]);
+        assert_eq!(
+            highlighter.get_diagnostic(1),
+            Some("Mismatched ']': '{' from line 2 is still open")
+        );
+        assert_eq!(highlighter.get_diagnostic(2), None);
+        assert_eq!(
+            highlighter.get_diagnostic(3),
+            Some("Unexpected '}' without matching '{'")
+        );
+
+        let highlighter = highlight(&["{", r#"  "a": [1, 2"#]);
+        assert_eq!(highlighter.get_diagnostic(0), Some("Unclosed '{'"));
+        assert_eq!(highlighter.get_diagnostic(1), Some("Unclosed '['"));
+    }
+
+    #[test]
+    fn rewinding_takes_back_what_the_lines_after_said() {
+        let mut highlighter = highlight(&["{", r#"  "a": 1,"#, r#"  "a": 2"#]);
+        assert_eq!(highlighter.get_diagnostic(0), Some("Unclosed '{'"));
+        assert_eq!(highlighter.get_diagnostic(2), Some(r#"Duplicate key "a""#));
+
+        highlighter.rewind(2);
+        assert_eq!(highlighter.highlighted_len(), 2);
+        assert_eq!(highlighter.get_diagnostic(0), None);
+        highlighter.highlight(2, &Line::from(r#"  "b": 2"#));
+        highlighter.highlight(3, &Line::from("}"));
+        highlighter.finish();
+        assert_eq!(highlighter.get_diagnostic(0), None);
+        assert_eq!(highlighter.get_diagnostic(2), None);
+
+        highlighter.rewind(1);
+        highlighter.highlight(1, &Line::from(r#"  "b": 1,"#));
+        highlighter.highlight(2, &Line::from(r#"  "b": 2"#));
+        assert_eq!(highlighter.get_diagnostic(2), Some(r#"Duplicate key "b""#));
+    }
+}
\ No newline at end of file
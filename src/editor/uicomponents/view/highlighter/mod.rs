@@ -1,43 +1,70 @@
 use super::super::super::{Annotation, AnnotationType, FileType, Line};
 use crate::{editor::{annotation, line}, prelude::*};
+mod highlights;
 mod syntaxhighlighter;
 use searchresulthighlighter::SearchResultHighlighter;
-use syntaxhighlighter::SyntaxHighlighter;
+pub use syntaxhighlighter::SyntaxHighlighter;
 mod rustsyntaxhighlighter;
 mod searchresulthighlighter;
+mod selectionhighlighter;
+use selectionhighlighter::SelectionHighlighter;
 use rustsyntaxhighlighter::RustSyntaxHighlighter;
+mod scalar;
+mod jsonsyntaxhighlighter;
+use jsonsyntaxhighlighter::JsonSyntaxHighlighter;
+mod tomlsyntaxhighlighter;
+use tomlsyntaxhighlighter::TomlSyntaxHighlighter;
+mod yamlsyntaxhighlighter;
+use yamlsyntaxhighlighter::YamlSyntaxHighlighter;
+mod scriptsyntaxhighlighter;
+use scriptsyntaxhighlighter::{ScriptSyntaxHighlighter, DOCKERFILE, MAKEFILE, PYTHON, SHELL};
 
-fn create_syntax_highlight(file_type: FileType) -> Option<Box<dyn SyntaxHighlighter>> {
+#[must_use]
+pub fn create_syntax_highlight(file_type: FileType) -> Option<Box<dyn SyntaxHighlighter>> {
     match file_type {
         FileType::Rust => Some(Box::<RustSyntaxHighlighter>::default()),
+        FileType::Json => Some(Box::<JsonSyntaxHighlighter>::default()),
+        FileType::Toml => Some(Box::<TomlSyntaxHighlighter>::default()),
+        FileType::Yaml => Some(Box::<YamlSyntaxHighlighter>::default()),
+        FileType::Python => Some(Box::new(ScriptSyntaxHighlighter::new(&PYTHON))),
+        FileType::Shell => Some(Box::new(ScriptSyntaxHighlighter::new(&SHELL))),
+        FileType::Makefile => Some(Box::new(ScriptSyntaxHighlighter::new(&MAKEFILE))),
+        FileType::Dockerfile => Some(Box::new(ScriptSyntaxHighlighter::new(&DOCKERFILE))),
         FileType::Text => None,
     }
 }
 
 #[derive(Default)]
+#[allow(clippy::struct_field_names)]
 pub struct Highlighter<'a> {
-    syntax_highlighter: Option<Box<dyn SyntaxHighlighter>>,
+    syntax_highlighter: Option<&'a dyn SyntaxHighlighter>,
     search_result_highlighter: Option<SearchResultHighlighter<'a>>,
+    selection_highlighter: Option<SelectionHighlighter>,
 }
 
 impl<'a> Highlighter<'a> {
+    #[must_use]
     pub fn new(
         matched_word: Option<&'a str>,
         selected_match: Option<Location>,
-        file_type: FileType,
+        selection: Option<(Location, Location)>,
+        syntax_highlighter: Option<&'a dyn SyntaxHighlighter>,
     ) -> Self {
         let search_result_highlighter = matched_word
             .map(|matched_word| SearchResultHighlighter::new(matched_word, selected_match));
         
         Self {
-            syntax_highlighter: create_syntax_highlight(file_type),
+            syntax_highlighter,
             search_result_highlighter,
+            selection_highlighter: selection
+                .map(|(start, end)| SelectionHighlighter::new(start, end)),
         }
     }
 
+    #[must_use]
     pub fn get_annotations(&self,idx: LineIdx) -> Vec<Annotation> {
         let mut result = Vec::new();
-        if let Some(syntax_highlighter) = &self.syntax_highlighter{
+        if let Some(syntax_highlighter) = self.syntax_highlighter {
             if let Some(annotations) = syntax_highlighter.get_annotations(idx) {
                 result.extend(annotations.iter().copied());
             }
@@ -48,20 +75,24 @@ impl<'a> Highlighter<'a> {
                 result.extend(annotations.iter().copied());
             }
         }
+
+        if let Some(selection_highlighter) = &self.selection_highlighter {
+            if let Some(annotations) = selection_highlighter.get_annotations(idx) {
+                result.extend(annotations.iter().copied());
+            }
+        }
         result
     }
 
+    /// Highlights the search results and the selection on `line`. Syntax
+    /// highlighting is up to the buffer.
     pub fn highlight(&mut self,idx: LineIdx,line: &Line) {
-        if let Some(syntax_highlighter) = &mut self.syntax_highlighter {
-            syntax_highlighter.highlight(idx, line);
-        }
-
         if let Some(search_result_highlighter) = &mut self.search_result_highlighter {
             search_result_highlighter.highlight(idx, line);
         }
 
-
+        if let Some(selection_highlighter) = &mut self.selection_highlighter {
+            selection_highlighter.highlight(idx, line);
+        }
     }
-
-
-}
+}
\ No newline at end of file
@@ -2,6 +2,7 @@ use std::{char, collections::HashMap, io::Result, result, string};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{
+    highlights::Highlights,
     syntaxhighlighter::{self, SyntaxHighlighter},
     Annotation, AnnotationType, Line,
 };
@@ -72,13 +73,19 @@ const TYPES: [&str; 22] = [
 
 const KONWN_VALUES: [&str; 6] = ["Some", "None", "true", "false", "Ok", "Err"];
 
-#[derive(Default)]
-pub struct RustSyntaxHighlighter {
-    highlights: Vec<Vec<Annotation>>,
+// What carries over from one line to the next.
+#[derive(Clone, Copy, Default)]
+struct State {
     ml_comment_balance: usize,
     in_ml_string: bool,
 }
 
+#[derive(Default)]
+pub struct RustSyntaxHighlighter {
+    highlights: Highlights<State>,
+    state: State,
+}
+
 impl RustSyntaxHighlighter {
     fn annotate_ml_comment(&mut self, string: &str) -> Option<Annotation> {
         let mut chars = string.char_indices().peekable();
@@ -86,15 +93,15 @@ impl RustSyntaxHighlighter {
         while let Some((_, char)) = chars.next() {
             if char == '/' {
                 if let Some((_, '*')) = chars.peek() {
-                    self.ml_comment_balance = self.ml_comment_balance.saturating_add(1);
+                    self.state.ml_comment_balance = self.state.ml_comment_balance.saturating_add(1);
                     chars.next();
                 }
-            } else if self.ml_comment_balance == 0 {
+            } else if self.state.ml_comment_balance == 0 {
                 return None;
             } else if char == '*' {
                 if let Some((idx, '/')) = chars.peek() {
-                    self.ml_comment_balance = self.ml_comment_balance.saturating_add(1);
-                    if self.ml_comment_balance == 0 {
+                    self.state.ml_comment_balance = self.state.ml_comment_balance.saturating_sub(1);
+                    if self.state.ml_comment_balance == 0 {
                         return Some(Annotation {
                             annotation_type: AnnotationType::Comment,
                             start: 0,
@@ -105,7 +112,7 @@ impl RustSyntaxHighlighter {
                 }
             }
         }
-        (self.ml_comment_balance > 0).then_some(Annotation {
+        (self.state.ml_comment_balance > 0).then_some(Annotation {
             annotation_type: AnnotationType::Comment,
             start: 0,
             end: string.len(),
@@ -116,29 +123,29 @@ impl RustSyntaxHighlighter {
         let mut chars = string.char_indices();
 
         while let Some((idx, char)) = chars.next() {
-            if char == '\\' && self.in_ml_string {
+            if char == '\\' && self.state.in_ml_string {
                 chars.next();
                 continue;
             }
 
             if char == '"' {
-                if self.in_ml_string {
-                    self.in_ml_string = false;
+                if self.state.in_ml_string {
+                    self.state.in_ml_string = false;
                     return Some(Annotation {
                         annotation_type: AnnotationType::String,
                         start: 0,
                         end: idx.saturating_add(1),
                     });
                 }
-                self.in_ml_string = true;
+                self.state.in_ml_string = true;
             }
 
-            if !self.in_ml_string {
+            if !self.state.in_ml_string {
                 return None;
             }
         }
 
-        self.in_ml_string.then_some(Annotation {
+        self.state.in_ml_string.then_some(Annotation {
             annotation_type: AnnotationType::String,
             start: 0,
             end: string.len(),
@@ -146,9 +153,9 @@ impl RustSyntaxHighlighter {
     }
 
     fn initial_annotation(&mut self, line: &Line) -> Option<Annotation> {
-        if self.in_ml_string {
+        if self.state.in_ml_string {
             self.annotate_string(line)
-        } else if self.ml_comment_balance > 0 {
+        } else if self.state.ml_comment_balance > 0 {
             self.annotate_ml_comment(line)
         } else {
             None
@@ -158,6 +165,7 @@ impl RustSyntaxHighlighter {
     fn annotate_remainder(&mut self, remainder: &str) -> Option<Annotation> {
         self.annotate_ml_comment(remainder)
             .or_else(|| annotate_single_line_comment(remainder))
+            .or_else(|| self.annotate_string(remainder))
             .or_else(|| annotate_char(remainder))
             .or_else(|| annotation_lifetime_specifier(remainder))
             .or_else(|| annotate_number(remainder))
@@ -170,6 +178,7 @@ impl RustSyntaxHighlighter {
 impl SyntaxHighlighter for RustSyntaxHighlighter {
     fn highlight(&mut self, idx: LineIdx, line: &Line) {
         debug_assert_eq!(idx, self.highlights.len());
+        let state = self.state;
         let mut result = Vec::new();
         let mut iterator = line.split_word_bound_indices().peekable();
         if let Some(annotation) = self.initial_annotation(line) {
@@ -197,12 +206,22 @@ impl SyntaxHighlighter for RustSyntaxHighlighter {
                 }
             };
         }
-        self.highlights.push(result);
+        self.highlights.push(state, result);
     }
 
     fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
         self.highlights.get(idx)
     }
+
+    fn rewind(&mut self, idx: LineIdx) {
+        if let Some(state) = self.highlights.rewind(idx) {
+            self.state = state;
+        }
+    }
+
+    fn highlighted_len(&self) -> LineIdx {
+        self.highlights.len()
+    }
 }
 
 fn annotation_next_word<F>(
@@ -0,0 +1,143 @@
+use crate::prelude::*;
+
+pub fn quoted_len(string: &str, escapes: bool) -> Option<ByteIdx> {
+    let mut chars = string.char_indices();
+    let (_, quote) = chars.next()?;
+    while let Some((idx, char)) = chars.next() {
+        if escapes && char == '\\' {
+            chars.next();
+            continue;
+        }
+        if char == quote {
+            return Some(idx.saturating_add(char.len_utf8()));
+        }
+    }
+    None
+}
+
+pub fn token_len(string: &str, delimiters: &[char]) -> ByteIdx {
+    string
+        .find(|char: char| char.is_whitespace() || delimiters.contains(&char))
+        .unwrap_or(string.len())
+}
+
+pub fn is_number(word: &str) -> bool {
+    let unsigned = word.strip_prefix(['+', '-']).unwrap_or(word);
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(digits) = unsigned.strip_prefix(prefix) {
+            return !digits.is_empty()
+                && digits
+                    .chars()
+                    .all(|char| char == '_' || char.is_digit(radix));
+        }
+    }
+    unsigned.starts_with(|char: char| char.is_ascii_digit())
+        && unsigned
+            .chars()
+            .all(|char| char.is_ascii_digit() || matches!(char, '_' | '.' | 'e' | 'E' | '+' | '-'))
+}
+
+/// Whether `word` is a TOML date, time or date and time, like
+/// `1979-05-27`, `07:32:00` or `1979-05-27T07:32:00.5-07:00`.
+pub fn is_date_time(word: &str) -> bool {
+    match word.split_once(['T', 't']) {
+        Some((date, time)) => is_date(date) && is_time(strip_offset(time)),
+        None if word.contains(':') => is_time(word),
+        None => is_date(word),
+    }
+}
+
+fn is_date(word: &str) -> bool {
+    matches_pattern(word, "dddd-dd-dd")
+}
+
+fn is_time(word: &str) -> bool {
+    let (time, fraction) = word.split_once('.').unwrap_or((word, "0"));
+    matches_pattern(time, "dd:dd:dd")
+        && !fraction.is_empty()
+        && fraction.chars().all(|char| char.is_ascii_digit())
+}
+
+fn strip_offset(time: &str) -> &str {
+    if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        return time;
+    }
+    let split = time.len().saturating_sub(6);
+    match (time.get(..split), time.get(split..)) {
+        (Some(time), Some(offset))
+            if matches_pattern(offset, "+dd:dd") || matches_pattern(offset, "-dd:dd") =>
+        {
+            time
+        }
+        _ => time,
+    }
+}
+
+// `d` in `pattern` stands for any digit.
+fn matches_pattern(word: &str, pattern: &str) -> bool {
+    word.len() == pattern.len()
+        && word.chars().zip(pattern.chars()).all(|(char, expected)| {
+            if expected == 'd' {
+                char.is_ascii_digit()
+            } else {
+                char == expected
+            }
+        })
+}
+
+pub fn is_boolean(word: &str) -> bool {
+    matches!(word, "true" | "false")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        for word in ["0", "-12", "+3.5", "1e10", "6.02E+23", "1_000", "0xff", "0o17", "0b101"] {
+            assert!(is_number(word), "{word}");
+        }
+        for word in ["", "-", "e5", "0x", "0xfg", "abc", "1a"] {
+            assert!(!is_number(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn dates_and_times_are_not_numbers() {
+        for word in ["12:30", "1979-05-27T07:32:00Z", "2024T", "10Z"] {
+            assert!(!is_number(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn date_times() {
+        for word in [
+            "1979-05-27",
+            "07:32:00",
+            "07:32:00.999",
+            "1979-05-27T07:32:00",
+            "1979-05-27t07:32:00z",
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T00:32:00.5-07:00",
+        ] {
+            assert!(is_date_time(word), "{word}");
+        }
+        for word in ["1979-5-27", "7:32:00", "07:32", "07:32:00.", "07:32:00Z", "1979-05-27T", "12"] {
+            assert!(!is_date_time(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn quoted() {
+        assert_eq!(quoted_len(r#""a\"b" c"#, true), Some(6));
+        assert_eq!(quoted_len(r"'a\' c", false), Some(4));
+        assert_eq!(quoted_len(r#""open"#, true), None);
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(token_len("true, false", &[',']), 4);
+        assert_eq!(token_len("word", &[]), 4);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,493 @@
+use super::{
+    highlights::Highlights,
+    scalar::{is_number, quoted_len},
+    syntaxhighlighter::SyntaxHighlighter,
+    Annotation, AnnotationType, Line,
+};
+use crate::prelude::*;
+
+/// Where a `#` starts a comment.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Comments {
+    Anywhere,
+    AfterWhitespace,
+    LineStart,
+}
+
+/// Where a keyword counts as one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Keywords {
+    Anywhere,
+    // As the first word of a line, in any case.
+    Leading,
+}
+
+/// What tells the languages highlighted by [`ScriptSyntaxHighlighter`]
+/// apart. They all have `#` comments, quoted strings and words.
+pub struct Language {
+    keywords: &'static [&'static str],
+    keyword_placement: Keywords,
+    types: &'static [&'static str],
+    known_values: &'static [&'static str],
+    comments: Comments,
+    // Quotes that keep a string open past the end of the line, the longest
+    // first.
+    multi_line_quotes: &'static [&'static str],
+    single_quote_escapes: bool,
+    // Whether `$name`, `${...}` and `$(...)` refer to variables.
+    variables: bool,
+    // Whether a line outside a recipe can define a variable or a target.
+    definitions: bool,
+}
+
+pub const PYTHON: Language = Language {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "case", "class", "continue", "def",
+        "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise", "return", "try",
+        "while", "with", "yield",
+    ],
+    keyword_placement: Keywords::Anywhere,
+    types: &[
+        "bool", "bytes", "dict", "float", "int", "list", "object", "set", "str", "tuple", "type",
+    ],
+    known_values: &["True", "False", "None"],
+    comments: Comments::Anywhere,
+    multi_line_quotes: &["\"\"\"", "'''"],
+    single_quote_escapes: true,
+    variables: false,
+    definitions: false,
+};
+
+pub const SHELL: Language = Language {
+    keywords: &[
+        "alias", "break", "case", "continue", "declare", "do", "done", "elif", "else", "esac",
+        "eval", "exec", "exit", "export", "fi", "for", "function", "if", "in", "local",
+        "readonly", "return", "select", "shift", "source", "then", "trap", "unset", "until",
+        "while",
+    ],
+    keyword_placement: Keywords::Anywhere,
+    types: &[],
+    known_values: &["true", "false"],
+    comments: Comments::AfterWhitespace,
+    multi_line_quotes: &["\"", "'"],
+    single_quote_escapes: false,
+    variables: true,
+    definitions: false,
+};
+
+pub const MAKEFILE: Language = Language {
+    keywords: &[
+        "define", "else", "endef", "endif", "export", "ifdef", "ifeq", "ifndef", "ifneq",
+        "include", "override", "private", "sinclude", "unexport", "vpath",
+    ],
+    keyword_placement: Keywords::Leading,
+    types: &[],
+    known_values: &[],
+    comments: Comments::Anywhere,
+    multi_line_quotes: &[],
+    single_quote_escapes: false,
+    variables: true,
+    definitions: true,
+};
+
+pub const DOCKERFILE: Language = Language {
+    keywords: &[
+        "ADD", "ARG", "CMD", "COPY", "ENTRYPOINT", "ENV", "EXPOSE", "FROM", "HEALTHCHECK",
+        "LABEL", "MAINTAINER", "ONBUILD", "RUN", "SHELL", "STOPSIGNAL", "USER", "VOLUME",
+        "WORKDIR",
+    ],
+    keyword_placement: Keywords::Leading,
+    types: &[],
+    known_values: &[],
+    comments: Comments::LineStart,
+    multi_line_quotes: &[],
+    single_quote_escapes: false,
+    variables: true,
+    definitions: false,
+};
+
+// What carries over from one line to the next.
+#[derive(Clone, Copy, Default)]
+struct State {
+    string_delimiter: Option<&'static str>,
+}
+
+/// Highlights Python, shell scripts, Makefiles and Dockerfiles.
+pub struct ScriptSyntaxHighlighter {
+    language: &'static Language,
+    highlights: Highlights<State>,
+    state: State,
+}
+
+impl ScriptSyntaxHighlighter {
+    pub fn new(language: &'static Language) -> Self {
+        Self {
+            language,
+            highlights: Highlights::default(),
+            state: State::default(),
+        }
+    }
+
+    fn escapes(&self, delimiter: &str) -> bool {
+        delimiter.starts_with('"') || self.language.single_quote_escapes
+    }
+
+    fn annotate_open_string(&mut self, line: &str, from: ByteIdx, result: &mut Vec<Annotation>) -> ByteIdx {
+        let Some(delimiter) = self.state.string_delimiter else {
+            return from;
+        };
+        let end = string_end(&line[from..], delimiter, self.escapes(delimiter)).map_or(line.len(), |len| {
+            self.state.string_delimiter = None;
+            from.saturating_add(len)
+        });
+        result.push(Annotation {
+            annotation_type: AnnotationType::String,
+            start: from,
+            end,
+        });
+        end
+    }
+
+    fn annotate_string(&mut self, line: &str, pos: ByteIdx, result: &mut Vec<Annotation>) -> ByteIdx {
+        let remainder = &line[pos..];
+        if let Some(delimiter) = self
+            .language
+            .multi_line_quotes
+            .iter()
+            .find(|delimiter| remainder.starts_with(**delimiter))
+        {
+            self.state.string_delimiter = Some(delimiter);
+            let end = self.annotate_open_string(line, pos.saturating_add(delimiter.len()), result);
+            if let Some(string) = result.last_mut() {
+                string.start = pos;
+            }
+            return end.saturating_sub(pos);
+        }
+        let escapes = self.escapes(remainder);
+        let len = quoted_len(remainder, escapes).unwrap_or(remainder.len());
+        result.push(Annotation {
+            annotation_type: AnnotationType::String,
+            start: pos,
+            end: pos.saturating_add(len),
+        });
+        len
+    }
+
+    fn is_comment(&self, line: &str, pos: ByteIdx) -> bool {
+        match self.language.comments {
+            Comments::Anywhere => true,
+            Comments::AfterWhitespace => line[..pos].chars().next_back().is_none_or(char::is_whitespace),
+            Comments::LineStart => line[..pos].trim().is_empty(),
+        }
+    }
+
+    fn classify(&self, word: &str, is_leading: bool) -> Option<AnnotationType> {
+        let language = self.language;
+        let is_keyword = if language.keyword_placement == Keywords::Leading {
+            is_leading
+                && language
+                    .keywords
+                    .iter()
+                    .any(|keyword| keyword.eq_ignore_ascii_case(word))
+        } else {
+            language.keywords.contains(&word)
+        };
+        if is_keyword {
+            Some(AnnotationType::Keyword)
+        } else if language.types.contains(&word) {
+            Some(AnnotationType::Type)
+        } else if language.known_values.contains(&word) {
+            Some(AnnotationType::KnownValue)
+        } else if is_number(word) {
+            Some(AnnotationType::Number)
+        } else {
+            None
+        }
+    }
+
+    fn annotate_words(&mut self, line: &str, from: ByteIdx, result: &mut Vec<Annotation>) {
+        let mut pos = from;
+        while let Some(char) = line[pos..].chars().next() {
+            let remainder = &line[pos..];
+            let len = match char {
+                '#' if self.is_comment(line, pos) => {
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Comment,
+                        start: pos,
+                        end: line.len(),
+                    });
+                    remainder.len()
+                }
+                '"' | '\'' => self.annotate_string(line, pos, result),
+                '$' if self.language.variables => {
+                    let len = variable_len(remainder);
+                    if len > 1 {
+                        result.push(Annotation {
+                            annotation_type: AnnotationType::Key,
+                            start: pos,
+                            end: pos.saturating_add(len),
+                        });
+                    }
+                    len
+                }
+                _ if is_word_char(char) => {
+                    let len = word_len(remainder);
+                    let end = pos.saturating_add(len);
+                    let is_leading = line[..pos].trim().is_empty();
+                    if let Some(annotation_type) = self.classify(&line[pos..end], is_leading) {
+                        result.push(Annotation {
+                            annotation_type,
+                            start: pos,
+                            end,
+                        });
+                    }
+                    len
+                }
+                _ => char.len_utf8(),
+            };
+            pos = pos.saturating_add(len);
+        }
+    }
+
+    // Marks the variable or the targets a Makefile line defines, and returns
+    // where the rest of the line starts.
+    fn annotate_definition(&self, line: &str, result: &mut Vec<Annotation>) -> ByteIdx {
+        if !self.language.definitions || line.starts_with('\t') {
+            return 0;
+        }
+        let start = line.len().saturating_sub(line.trim_start().len());
+        let content = &line[start..];
+        if content.starts_with('#') {
+            return 0;
+        }
+        let equals = content.find('=');
+        let colon = content.find(':');
+        // `:=` and `::=` assign, while a `:` further left ends the targets.
+        let is_assignment = equals.is_some_and(|equals| {
+            colon.is_none_or(|colon| colon.saturating_add(2) >= equals)
+        });
+        let name = match (equals, colon) {
+            (Some(equals), _) if is_assignment => content[..equals]
+                .trim_end_matches(['?', '+', '!', ':'])
+                .trim_end(),
+            (_, Some(colon)) => content[..colon].trim_end(),
+            _ => return 0,
+        };
+        if name.is_empty() || (is_assignment && name.contains(char::is_whitespace)) {
+            return 0;
+        }
+        let end = start.saturating_add(name.len());
+        result.push(Annotation {
+            annotation_type: AnnotationType::Key,
+            start,
+            end,
+        });
+        end
+    }
+}
+
+impl SyntaxHighlighter for ScriptSyntaxHighlighter {
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        debug_assert_eq!(idx, self.highlights.len());
+        let state = self.state;
+        let mut result = Vec::new();
+
+        let mut pos = self.annotate_open_string(line, 0, &mut result);
+        if self.state.string_delimiter.is_none() {
+            if pos == 0 {
+                pos = self.annotate_definition(line, &mut result);
+            }
+            self.annotate_words(line, pos, &mut result);
+        }
+        self.highlights.push(state, result);
+    }
+
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(idx)
+    }
+
+    fn rewind(&mut self, idx: LineIdx) {
+        if let Some(state) = self.highlights.rewind(idx) {
+            self.state = state;
+        }
+    }
+
+    fn highlighted_len(&self) -> LineIdx {
+        self.highlights.len()
+    }
+}
+
+// The length of `string` up to and including the first unescaped
+// `delimiter`.
+fn string_end(string: &str, delimiter: &str, escapes: bool) -> Option<ByteIdx> {
+    let mut chars = string.char_indices();
+    while let Some((idx, char)) = chars.next() {
+        if escapes && char == '\\' {
+            chars.next();
+        } else if string[idx..].starts_with(delimiter) {
+            return Some(idx.saturating_add(delimiter.len()));
+        }
+    }
+    None
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
+fn word_len(string: &str) -> ByteIdx {
+    let is_numeric = string.starts_with(|char: char| char.is_ascii_digit());
+    string
+        .find(|char: char| !(is_word_char(char) || (is_numeric && char == '.')))
+        .unwrap_or(string.len())
+}
+
+// The length of the variable reference `string` starts with: `$name`,
+// `${...}`, `$(...)` or `$` and a single character like `$@` or `$1`.
+fn variable_len(string: &str) -> ByteIdx {
+    let rest = &string[1..];
+    let close = if rest.starts_with('{') {
+        Some('}')
+    } else if rest.starts_with('(') {
+        Some(')')
+    } else {
+        None
+    };
+    if let Some(close) = close {
+        return rest
+            .find(close)
+            .map_or(string.len(), |idx| idx.saturating_add(2));
+    }
+    match rest.chars().next() {
+        Some(char) if char.is_alphabetic() || char == '_' => word_len(rest).saturating_add(1),
+        Some(char) if !char.is_whitespace() && !matches!(char, '"' | '\'') => {
+            char.len_utf8().saturating_add(1)
+        }
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(language: &'static Language, lines: &[&str]) -> ScriptSyntaxHighlighter {
+        let mut highlighter = ScriptSyntaxHighlighter::new(language);
+        for (idx, line) in lines.iter().enumerate() {
+            highlighter.highlight(idx, &Line::from(line));
+        }
+        highlighter
+    }
+
+    fn annotated<'a>(highlighter: &ScriptSyntaxHighlighter, lines: &[&'a str], idx: LineIdx) -> Vec<(AnnotationType, &'a str)> {
+        highlighter
+            .get_annotations(idx)
+            .into_iter()
+            .flatten()
+            .map(|annotation| (annotation.annotation_type, &lines[idx][annotation.start..annotation.end]))
+            .collect()
+    }
+
+    #[test]
+    fn python() {
+        let lines = [
+            "def f(x: int) -> None:  # doc",
+            r#"    s = 'it\'s' + """a"#,
+            r#"  # not a comment """ if x else 1.5"#,
+        ];
+        let highlighter = highlight(&PYTHON, &lines);
+        assert_eq!(
+            annotated(&highlighter, &lines, 0),
+            [
+                (AnnotationType::Keyword, "def"),
+                (AnnotationType::Type, "int"),
+                (AnnotationType::KnownValue, "None"),
+                (AnnotationType::Comment, "# doc"),
+            ]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 1),
+            [(AnnotationType::String, r"'it\'s'"), (AnnotationType::String, r#""""a"#)]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [
+                (AnnotationType::String, r#"  # not a comment """"#),
+                (AnnotationType::Keyword, "if"),
+                (AnnotationType::Keyword, "else"),
+                (AnnotationType::Number, "1.5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn shell() {
+        let lines = [r#"if [ "$1" = a#b ]; then # check"#, r"  echo ${HOME} $@ 'two", r"lines\' done"];
+        let highlighter = highlight(&SHELL, &lines);
+        assert_eq!(
+            annotated(&highlighter, &lines, 0),
+            [
+                (AnnotationType::Keyword, "if"),
+                (AnnotationType::String, r#""$1""#),
+                (AnnotationType::Keyword, "then"),
+                (AnnotationType::Comment, "# check"),
+            ]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 1),
+            [
+                (AnnotationType::Key, "${HOME}"),
+                (AnnotationType::Key, "$@"),
+                (AnnotationType::String, "'two"),
+            ]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [(AnnotationType::String, r"lines\'"), (AnnotationType::Keyword, "done")]
+        );
+    }
+
+    #[test]
+    fn makefile() {
+        let lines = ["CC ?= gcc # compiler", "all: $(OUT)", "\techo $@: done", "ifeq ($(CC),gcc)"];
+        let highlighter = highlight(&MAKEFILE, &lines);
+        assert_eq!(
+            annotated(&highlighter, &lines, 0),
+            [(AnnotationType::Key, "CC"), (AnnotationType::Comment, "# compiler")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 1),
+            [(AnnotationType::Key, "all"), (AnnotationType::Key, "$(OUT)")]
+        );
+        assert_eq!(annotated(&highlighter, &lines, 2), [(AnnotationType::Key, "$@")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 3),
+            [(AnnotationType::Keyword, "ifeq"), (AnnotationType::Key, "$(CC)")]
+        );
+    }
+
+    #[test]
+    fn dockerfile() {
+        let lines = ["# syntax", "from rust AS build", "RUN echo \"$HOME\" # from run"];
+        let highlighter = highlight(&DOCKERFILE, &lines);
+        assert_eq!(annotated(&highlighter, &lines, 0), [(AnnotationType::Comment, "# syntax")]);
+        assert_eq!(annotated(&highlighter, &lines, 1), [(AnnotationType::Keyword, "from")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [(AnnotationType::Keyword, "RUN"), (AnnotationType::String, "\"$HOME\"")]
+        );
+    }
+
+    #[test]
+    fn rewinding_resumes_in_the_state_of_the_line() {
+        let lines = ["x = 1", r#"s = """"#, "if"];
+        let mut highlighter = highlight(&PYTHON, &lines);
+        assert_eq!(annotated(&highlighter, &lines, 2), [(AnnotationType::String, "if")]);
+
+        highlighter.rewind(1);
+        highlighter.highlight(1, &Line::from("s = 1"));
+        highlighter.highlight(2, &Line::from("if"));
+        assert_eq!(annotated(&highlighter, &lines, 2), [(AnnotationType::Keyword, "if")]);
+    }
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::{collections::HashMap, result};
-use super::{syntaxhighlighter::SyntaxHighlighter, Annotation, AnnotationType, Line};
-use crate::{editor::line, prelude::*};
+use std::collections::HashMap;
+use super::{Annotation, AnnotationType, Line};
+use crate::prelude::*;
 
 pub struct SearchResultHighlighter<'a> {
     matched_word: &'a str,
@@ -18,6 +18,17 @@ impl<'a> SearchResultHighlighter<'a> {
         }
     }
 
+    pub fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        let mut result = Vec::new();
+        self.highlight_matched_words(line, &mut result);
+        self.highlight_selected_match(idx, line, &mut result);
+        self.highlights.insert(idx, result);
+    }
+
+    pub fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+
     fn highlight_matched_words(&self,line: &Line,result: &mut Vec<Annotation>) {
         if self.matched_word.is_empty() {
             return;
@@ -34,30 +45,23 @@ impl<'a> SearchResultHighlighter<'a> {
         });
     }
 
-    fn highlight_selected_match(&self,result: &mut Vec<Annotation>) {
+    fn highlight_selected_match(&self,idx: LineIdx,line: &Line,result: &mut Vec<Annotation>) {
         if let Some(selected_match) = self.selected_match {
-            if self.matched_word.is_empty() {
+            if self.matched_word.is_empty() || selected_match.line_idx != idx {
                 return;
             }
 
-            let start = selected_match.grapheme_idx;
-            result.push(Annotation {
-                annotation_type: AnnotationType::SelectedMatch,
-                start,
-                end: start.saturating_add(self.matched_word.len()),
-            });
+            let selected = line
+                .find_all(self.matched_word, 0..line.len())
+                .into_iter()
+                .find(|(_, grapheme_idx)| *grapheme_idx == selected_match.grapheme_idx);
+            if let Some((start, _)) = selected {
+                result.push(Annotation {
+                    annotation_type: AnnotationType::SelectedMatch,
+                    start,
+                    end: start.saturating_add(self.matched_word.len()),
+                });
+            }
         }
     }
-}
-
-impl<'a> SyntaxHighlighter for SearchResultHighlighter<'a>  {
-    fn highlight(&mut self, idx: LineIdx, line: &Line) {
-        let mut result = Vec::new();
-        self.highlight_matched_words(line, &mut result);
-        
-    }
-
-    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
-        todo!()
-    }
 }
\ No newline at end of file
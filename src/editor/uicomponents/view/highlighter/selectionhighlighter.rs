@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use super::{Annotation, AnnotationType, Line};
+use crate::prelude::*;
+
+pub struct SelectionHighlighter {
+    start: Location,
+    end: Location,
+    highlights: HashMap<LineIdx, Vec<Annotation>>,
+}
+
+impl SelectionHighlighter {
+    pub fn new(start: Location, end: Location) -> Self {
+        Self {
+            start,
+            end,
+            highlights: HashMap::new(),
+        }
+    }
+
+    pub fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        if idx < self.start.line_idx || idx > self.end.line_idx {
+            return;
+        }
+
+        let start = if idx == self.start.line_idx {
+            line.grapheme_idx_to_byte_idx(self.start.grapheme_idx)
+        } else {
+            0
+        };
+        let end = if idx == self.end.line_idx {
+            line.grapheme_idx_to_byte_idx(self.end.grapheme_idx)
+        } else {
+            line.len()
+        };
+
+        if start < end {
+            self.highlights.insert(
+                idx,
+                vec![Annotation {
+                    annotation_type: AnnotationType::Selection,
+                    start,
+                    end,
+                }],
+            );
+        }
+    }
+
+    pub fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(&idx)
+    }
+}
\ No newline at end of file
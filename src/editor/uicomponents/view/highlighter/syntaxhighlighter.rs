@@ -1,7 +1,25 @@
 use super::Annotation;
 use super::Line;
 use crate::prelude::*;
+
+/// Highlights a document line by line, from the top, keeping what it found
+/// until the lines change.
 pub trait SyntaxHighlighter {
     fn highlight(&mut self, idx: LineIdx, line: &Line);
     fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>>;
+
+    /// Called once the last line of the document was highlighted.
+    fn finish(&mut self) {}
+
+    fn get_diagnostic(&self, _idx: LineIdx) -> Option<&str> {
+        None
+    }
+
+    /// Forgets line `idx` and the lines after it, so that highlighting
+    /// continues there once they changed.
+    fn rewind(&mut self, idx: LineIdx);
+
+    /// The number of lines highlighted so far, which is where highlighting
+    /// continues.
+    fn highlighted_len(&self) -> LineIdx;
 }
\ No newline at end of file
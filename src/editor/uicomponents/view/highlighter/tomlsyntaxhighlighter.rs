@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use super::{
+    highlights::Highlights,
+    scalar::{is_boolean, is_date_time, is_number, quoted_len, token_len},
+    syntaxhighlighter::SyntaxHighlighter,
+    Annotation, AnnotationType, Line,
+};
+use crate::prelude::*;
+
+const DELIMITERS: [char; 6] = [',', '=', '[', ']', '{', '}'];
+
+const SPECIAL_FLOATS: [&str; 6] = ["inf", "+inf", "-inf", "nan", "+nan", "-nan"];
+
+// What carries over from one line to the next.
+#[derive(Clone, Copy, Default)]
+struct State {
+    ml_string_delimiter: Option<&'static str>,
+    array_depth: usize,
+}
+
+#[derive(Default)]
+pub struct TomlSyntaxHighlighter {
+    highlights: Highlights<State>,
+    state: State,
+    // The tables defined so far, each with the line of its header.
+    tables: HashMap<String, LineIdx>,
+}
+
+impl TomlSyntaxHighlighter {
+    fn report(&mut self, idx: LineIdx, errors: &mut Vec<Annotation>, start: ByteIdx, end: ByteIdx, message: String) {
+        errors.push(Annotation {
+            annotation_type: AnnotationType::Error,
+            start,
+            end,
+        });
+        self.highlights.report(idx, message);
+    }
+
+    fn annotate_ml_string(&mut self, line: &str, from: ByteIdx, result: &mut Vec<Annotation>) -> ByteIdx {
+        let Some(delimiter) = self.state.ml_string_delimiter else {
+            return from;
+        };
+        let end = line[from..].find(delimiter).map_or(line.len(), |idx| {
+            self.state.ml_string_delimiter = None;
+            from.saturating_add(idx).saturating_add(delimiter.len())
+        });
+        result.push(Annotation {
+            annotation_type: AnnotationType::String,
+            start: from,
+            end,
+        });
+        end
+    }
+
+    fn annotate_table_header(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        start: ByteIdx,
+        result: &mut Vec<Annotation>,
+        errors: &mut Vec<Annotation>,
+    ) -> ByteIdx {
+        let is_array_table = line[start..].starts_with("[[");
+        let (open, close) = if is_array_table { ("[[", "]]") } else { ("[", "]") };
+        let name_start = start.saturating_add(open.len());
+
+        let Some(name_len) = line[name_start..].find(close) else {
+            self.report(idx, errors, start, line.len(), "Unclosed table header".to_string());
+            return line.len();
+        };
+        let name_end = name_start.saturating_add(name_len);
+        let end = name_end.saturating_add(close.len());
+        let name = line[name_start..name_end].trim();
+
+        result.push(Annotation {
+            annotation_type: AnnotationType::Keyword,
+            start,
+            end,
+        });
+
+        if name.is_empty() || !is_valid_key(name) {
+            self.report(idx, errors, start, end, format!("Invalid table name '{name}'"));
+        } else if !is_array_table && *self.tables.entry(name.to_string()).or_insert(idx) != idx {
+            self.report(idx, errors, start, end, format!("Table [{name}] is defined more than once"));
+        }
+
+        let trailing = line[end..].trim_start();
+        if !trailing.is_empty() && !trailing.starts_with('#') {
+            let trailing_start = line.len().saturating_sub(trailing.len());
+            self.report(
+                idx,
+                errors,
+                trailing_start,
+                line.len(),
+                "Unexpected text after table header".to_string(),
+            );
+            return line.len();
+        }
+        end
+    }
+
+    fn annotate_key(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        start: ByteIdx,
+        result: &mut Vec<Annotation>,
+        errors: &mut Vec<Annotation>,
+    ) -> ByteIdx {
+        let Some(equals) = find_outside_quotes(&line[start..], '=') else {
+            self.report(idx, errors, start, line.len(), "Expected '=' after key".to_string());
+            return line.len();
+        };
+        let key = line[start..start.saturating_add(equals)].trim_end();
+        let end = start.saturating_add(key.len());
+        if is_valid_key(key) {
+            result.push(Annotation {
+                annotation_type: AnnotationType::Key,
+                start,
+                end,
+            });
+        } else {
+            self.report(idx, errors, start, end, format!("Invalid key '{key}'"));
+        }
+        start.saturating_add(equals).saturating_add(1)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn annotate_values(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        from: ByteIdx,
+        result: &mut Vec<Annotation>,
+        errors: &mut Vec<Annotation>,
+    ) {
+        let mut inline_table_depth: usize = 0;
+        let mut pos = from;
+
+        while let Some(char) = line[pos..].chars().next() {
+            let remainder = &line[pos..];
+            let len = match char {
+                '#' => {
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Comment,
+                        start: pos,
+                        end: line.len(),
+                    });
+                    remainder.len()
+                }
+                '"' | '\'' if remainder.starts_with("\"\"\"") || remainder.starts_with("'''") => {
+                    self.state.ml_string_delimiter = Some(if char == '"' { "\"\"\"" } else { "'''" });
+                    let end = self.annotate_ml_string(line, pos.saturating_add(3), result);
+                    if let Some(string) = result.last_mut() {
+                        string.start = pos;
+                    }
+                    end.saturating_sub(pos)
+                }
+                '"' | '\'' => {
+                    if let Some(len) = quoted_len(remainder, char == '"') {
+                        let end = pos.saturating_add(len);
+                        let is_key = inline_table_depth > 0 && line[end..].trim_start().starts_with('=');
+                        result.push(Annotation {
+                            annotation_type: if is_key {
+                                AnnotationType::Key
+                            } else {
+                                AnnotationType::String
+                            },
+                            start: pos,
+                            end,
+                        });
+                        len
+                    } else {
+                        self.report(idx, errors, pos, line.len(), "Unterminated string".to_string());
+                        remainder.len()
+                    }
+                }
+                '[' => {
+                    self.state.array_depth = self.state.array_depth.saturating_add(1);
+                    1
+                }
+                ']' => {
+                    if self.state.array_depth == 0 {
+                        self.report(idx, errors, pos, pos.saturating_add(1), "Unexpected ']'".to_string());
+                    }
+                    self.state.array_depth = self.state.array_depth.saturating_sub(1);
+                    1
+                }
+                '{' => {
+                    inline_table_depth = inline_table_depth.saturating_add(1);
+                    1
+                }
+                '}' => {
+                    if inline_table_depth == 0 {
+                        self.report(idx, errors, pos, pos.saturating_add(1), "Unexpected '}'".to_string());
+                    }
+                    inline_table_depth = inline_table_depth.saturating_sub(1);
+                    1
+                }
+                ',' | '=' => 1,
+                _ if char.is_whitespace() => char.len_utf8(),
+                _ => {
+                    let len = token_len(remainder, &DELIMITERS).max(char.len_utf8());
+                    let end = pos.saturating_add(len);
+                    let word = &line[pos..end];
+                    let annotation_type = if inline_table_depth > 0
+                        && line[end..].trim_start().starts_with('=')
+                    {
+                        Some(AnnotationType::Key)
+                    } else if is_boolean(word) {
+                        Some(AnnotationType::KnownValue)
+                    } else if is_number(word) || is_date_time(word) || SPECIAL_FLOATS.contains(&word) {
+                        Some(AnnotationType::Number)
+                    } else {
+                        None
+                    };
+                    if let Some(annotation_type) = annotation_type {
+                        result.push(Annotation {
+                            annotation_type,
+                            start: pos,
+                            end,
+                        });
+                    } else {
+                        self.report(idx, errors, pos, end, format!("Invalid value '{word}'"));
+                    }
+                    len
+                }
+            };
+            pos = pos.saturating_add(len);
+        }
+
+        if inline_table_depth > 0 {
+            self.report(
+                idx,
+                errors,
+                from,
+                line.len(),
+                "Inline tables must be closed on the same line".to_string(),
+            );
+        }
+    }
+}
+
+impl SyntaxHighlighter for TomlSyntaxHighlighter {
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        debug_assert_eq!(idx, self.highlights.len());
+        let state = self.state;
+        let mut result = Vec::new();
+        let mut errors = Vec::new();
+
+        let mut pos = self.annotate_ml_string(line, 0, &mut result);
+        if self.state.ml_string_delimiter.is_none() {
+            let content_start = line.len().saturating_sub(line.trim_start().len());
+            let content = &line[content_start..];
+            if self.state.array_depth > 0 || pos > 0 || content.is_empty() || content.starts_with('#') {
+                pos = pos.max(content_start);
+            } else if content.starts_with('[') {
+                pos = self.annotate_table_header(idx, line, content_start, &mut result, &mut errors);
+            } else {
+                pos = self.annotate_key(idx, line, content_start, &mut result, &mut errors);
+            }
+            self.annotate_values(idx, line, pos, &mut result, &mut errors);
+        }
+
+        result.append(&mut errors);
+        self.highlights.push(state, result);
+    }
+
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(idx)
+    }
+
+    fn get_diagnostic(&self, idx: LineIdx) -> Option<&str> {
+        self.highlights.diagnostic(idx)
+    }
+
+    fn rewind(&mut self, idx: LineIdx) {
+        if let Some(state) = self.highlights.rewind(idx) {
+            self.state = state;
+            self.tables.retain(|_, line_idx| *line_idx < idx);
+        }
+    }
+
+    fn highlighted_len(&self) -> LineIdx {
+        self.highlights.len()
+    }
+}
+
+fn find_outside_quotes(string: &str, needle: char) -> Option<ByteIdx> {
+    let mut pos = 0;
+    while let Some(char) = string[pos..].chars().next() {
+        if char == needle {
+            return Some(pos);
+        }
+        let len = if char == '"' || char == '\'' {
+            quoted_len(&string[pos..], char == '"').unwrap_or(string.len().saturating_sub(pos))
+        } else {
+            char.len_utf8()
+        };
+        pos = pos.saturating_add(len);
+    }
+    None
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut remainder = key.trim_start();
+    loop {
+        let len = if remainder.starts_with('"') || remainder.starts_with('\'') {
+            quoted_len(remainder, remainder.starts_with('"'))
+        } else {
+            remainder
+                .find(|char: char| !(char.is_ascii_alphanumeric() || char == '_' || char == '-'))
+                .or(Some(remainder.len()))
+                .filter(|len| *len > 0)
+        };
+        let Some(len) = len else {
+            return false;
+        };
+        remainder = remainder[len..].trim_start();
+        if remainder.is_empty() {
+            return true;
+        }
+        let Some(next) = remainder.strip_prefix('.') else {
+            return false;
+        };
+        remainder = next.trim_start();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(lines: &[&str]) -> TomlSyntaxHighlighter {
+        let mut highlighter = TomlSyntaxHighlighter::default();
+        for (idx, line) in lines.iter().enumerate() {
+            highlighter.highlight(idx, &Line::from(line));
+        }
+        highlighter.finish();
+        highlighter
+    }
+
+    fn annotated<'a>(highlighter: &TomlSyntaxHighlighter, lines: &[&'a str], idx: LineIdx) -> Vec<(AnnotationType, &'a str)> {
+        highlighter
+            .get_annotations(idx)
+            .into_iter()
+            .flatten()
+            .map(|annotation| (annotation.annotation_type, &lines[idx][annotation.start..annotation.end]))
+            .collect()
+    }
+
+    #[test]
+    fn values() {
+        let lines = [
+            "[package]",
+            r#"name = "hecto" # the name"#,
+            "version.major = 1_000",
+            "published = 1979-05-27T07:32:00Z",
+            "features = [true, inf]",
+        ];
+        let highlighter = highlight(&lines);
+        assert_eq!(annotated(&highlighter, &lines, 0), [(AnnotationType::Keyword, "[package]")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 1),
+            [
+                (AnnotationType::Key, "name"),
+                (AnnotationType::String, r#""hecto""#),
+                (AnnotationType::Comment, "# the name"),
+            ]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [(AnnotationType::Key, "version.major"), (AnnotationType::Number, "1_000")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 3),
+            [(AnnotationType::Key, "published"), (AnnotationType::Number, "1979-05-27T07:32:00Z")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 4),
+            [
+                (AnnotationType::Key, "features"),
+                (AnnotationType::KnownValue, "true"),
+                (AnnotationType::Number, "inf"),
+            ]
+        );
+        assert!((0..lines.len()).all(|idx| highlighter.get_diagnostic(idx).is_none()));
+    }
+
+    #[test]
+    fn errors() {
+        let highlighter = highlight(&[
+            "[a]",
+            "[a]",
+            "[b c]",
+            "[d",
+            "[e] x",
+            "f = yes",
+            "g",
+            "h = { i = 1",
+        ]);
+        assert_eq!(highlighter.get_diagnostic(0), None);
+        assert_eq!(highlighter.get_diagnostic(1), Some("Table [a] is defined more than once"));
+        assert_eq!(highlighter.get_diagnostic(2), Some("Invalid table name 'b c'"));
+        assert_eq!(highlighter.get_diagnostic(3), Some("Unclosed table header"));
+        assert_eq!(highlighter.get_diagnostic(4), Some("Unexpected text after table header"));
+        assert_eq!(highlighter.get_diagnostic(5), Some("Invalid value 'yes'"));
+        assert_eq!(highlighter.get_diagnostic(6), Some("Expected '=' after key"));
+        assert_eq!(
+            highlighter.get_diagnostic(7),
+            Some("Inline tables must be closed on the same line")
+        );
+    }
+
+    #[test]
+    fn multi_line_strings_and_arrays() {
+        let lines = [r#"text = """"#, "[not a table]", r#"""""#, "list = [", "  1,", "]"];
+        let highlighter = highlight(&lines);
+        assert_eq!(annotated(&highlighter, &lines, 1), [(AnnotationType::String, "[not a table]")]);
+        assert_eq!(annotated(&highlighter, &lines, 4), [(AnnotationType::Number, "1")]);
+        assert!((0..lines.len()).all(|idx| highlighter.get_diagnostic(idx).is_none()));
+    }
+
+    #[test]
+    fn rewinding_resumes_in_the_state_of_the_line() {
+        let mut highlighter = highlight(&["[a]", r#"s = """"#, "[a]", r#"""""#]);
+        assert_eq!(highlighter.get_diagnostic(2), None);
+
+        highlighter.rewind(1);
+        highlighter.highlight(1, &Line::from("s = 1"));
+        highlighter.highlight(2, &Line::from("[a]"));
+        assert_eq!(highlighter.get_diagnostic(2), Some("Table [a] is defined more than once"));
+
+        highlighter.rewind(0);
+        highlighter.highlight(0, &Line::from("[b]"));
+        highlighter.highlight(1, &Line::from("[a]"));
+        assert_eq!(highlighter.get_diagnostic(1), None);
+    }
+}
\ No newline at end of file
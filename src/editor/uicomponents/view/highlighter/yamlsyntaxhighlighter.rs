@@ -0,0 +1,418 @@
+use super::{
+    highlights::Highlights,
+    scalar::{is_number, quoted_len, token_len},
+    syntaxhighlighter::SyntaxHighlighter,
+    Annotation, AnnotationType, Line,
+};
+use crate::prelude::*;
+
+const FLOW_DELIMITERS: [char; 5] = [',', '[', ']', '{', '}'];
+
+const KNOWN_VALUES: [&str; 16] = [
+    "true", "True", "TRUE", "false", "False", "FALSE", "yes", "Yes", "no", "No", "on", "off",
+    "null", "Null", "NULL", "~",
+];
+
+#[derive(Clone)]
+struct FlowFrame {
+    bracket: char,
+    line_idx: LineIdx,
+    start: ByteIdx,
+}
+
+// What carries over from one line to the next.
+#[derive(Clone, Default)]
+struct State {
+    flow_frames: Vec<FlowFrame>,
+    block_scalar_indent: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct YamlSyntaxHighlighter {
+    highlights: Highlights<State>,
+    state: State,
+}
+
+impl YamlSyntaxHighlighter {
+    fn report(&mut self, idx: LineIdx, errors: &mut Vec<Annotation>, start: ByteIdx, end: ByteIdx, message: String) {
+        errors.push(Annotation {
+            annotation_type: AnnotationType::Error,
+            start,
+            end,
+        });
+        self.highlights.report(idx, message);
+    }
+
+    fn annotate_block_scalar(&mut self, line: &str, indent: usize, result: &mut Vec<Annotation>) -> bool {
+        let Some(parent_indent) = self.state.block_scalar_indent else {
+            return false;
+        };
+        if !line.trim().is_empty() && indent <= parent_indent {
+            self.state.block_scalar_indent = None;
+            return false;
+        }
+        result.push(Annotation {
+            annotation_type: AnnotationType::String,
+            start: indent,
+            end: line.len(),
+        });
+        true
+    }
+
+    fn annotate_key(&self, line: &str, start: ByteIdx, result: &mut Vec<Annotation>) -> ByteIdx {
+        if !self.state.flow_frames.is_empty() {
+            return start;
+        }
+        let remainder = &line[start..];
+        if remainder.starts_with(['[', '{', '#', '|', '>', '&', '*', '!']) {
+            return start;
+        }
+        let key_len = if remainder.starts_with('"') || remainder.starts_with('\'') {
+            quoted_len(remainder, remainder.starts_with('"'))
+                .filter(|len| remainder[*len..].trim_start().starts_with(':'))
+        } else {
+            find_mapping_colon(remainder)
+        };
+        let Some(key_len) = key_len else {
+            return start;
+        };
+        let end = start.saturating_add(key_len);
+        result.push(Annotation {
+            annotation_type: AnnotationType::Key,
+            start,
+            end,
+        });
+        line[end..]
+            .find(':')
+            .map_or(line.len(), |colon| end.saturating_add(colon).saturating_add(1))
+    }
+
+    fn close_flow(&mut self, idx: LineIdx, closing: char, at: ByteIdx, errors: &mut Vec<Annotation>) {
+        let expected = if closing == '}' { '{' } else { '[' };
+        match self.state.flow_frames.pop() {
+            Some(frame) if frame.bracket == expected => {}
+            Some(frame) => self.report(
+                idx,
+                errors,
+                at,
+                at.saturating_add(1),
+                format!(
+                    "Mismatched '{closing}': '{}' from line {} is still open",
+                    frame.bracket,
+                    frame.line_idx.saturating_add(1)
+                ),
+            ),
+            None => self.report(
+                idx,
+                errors,
+                at,
+                at.saturating_add(1),
+                format!("Unexpected '{closing}' without matching '{expected}'"),
+            ),
+        }
+    }
+
+    fn annotate_values(
+        &mut self,
+        idx: LineIdx,
+        line: &str,
+        indent: usize,
+        from: ByteIdx,
+        result: &mut Vec<Annotation>,
+        errors: &mut Vec<Annotation>,
+    ) {
+        let mut pos = from;
+
+        while let Some(char) = line[pos..].chars().next() {
+            let remainder = &line[pos..];
+            let preceded_by_space = pos == 0 || line[..pos].ends_with(char::is_whitespace);
+            let len = match char {
+                '#' if preceded_by_space => {
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Comment,
+                        start: pos,
+                        end: line.len(),
+                    });
+                    remainder.len()
+                }
+                '"' | '\'' => {
+                    if let Some(len) = quoted_len(remainder, char == '"') {
+                        let end = pos.saturating_add(len);
+                        let is_key = line[end..].trim_start().starts_with(':');
+                        result.push(Annotation {
+                            annotation_type: if is_key {
+                                AnnotationType::Key
+                            } else {
+                                AnnotationType::String
+                            },
+                            start: pos,
+                            end,
+                        });
+                        len
+                    } else {
+                        self.report(idx, errors, pos, line.len(), "Unterminated string".to_string());
+                        remainder.len()
+                    }
+                }
+                '[' | '{' => {
+                    self.state.flow_frames.push(FlowFrame {
+                        bracket: char,
+                        line_idx: idx,
+                        start: pos,
+                    });
+                    1
+                }
+                ']' | '}' => {
+                    self.close_flow(idx, char, pos, errors);
+                    1
+                }
+                '|' | '>' if self.state.flow_frames.is_empty() => {
+                    let len = token_len(remainder, &[]);
+                    self.state.block_scalar_indent = Some(indent);
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Keyword,
+                        start: pos,
+                        end: pos.saturating_add(len),
+                    });
+                    len
+                }
+                '&' | '*' | '!' => {
+                    let len = token_len(remainder, &FLOW_DELIMITERS);
+                    result.push(Annotation {
+                        annotation_type: AnnotationType::Type,
+                        start: pos,
+                        end: pos.saturating_add(len),
+                    });
+                    len
+                }
+                ',' | ':' => 1,
+                _ if char.is_whitespace() => char.len_utf8(),
+                _ => {
+                    let len = self.scalar_len(remainder);
+                    let end = pos.saturating_add(len);
+                    let word = line[pos..end].trim_end();
+                    let annotation_type = if line[end..].starts_with(':') {
+                        Some(AnnotationType::Key)
+                    } else if KNOWN_VALUES.contains(&word) {
+                        Some(AnnotationType::KnownValue)
+                    } else if is_number(word) {
+                        Some(AnnotationType::Number)
+                    } else {
+                        None
+                    };
+                    if let Some(annotation_type) = annotation_type {
+                        result.push(Annotation {
+                            annotation_type,
+                            start: pos,
+                            end: pos.saturating_add(word.len()),
+                        });
+                    }
+                    len.max(char.len_utf8())
+                }
+            };
+            pos = pos.saturating_add(len);
+        }
+    }
+
+    fn scalar_len(&self, string: &str) -> ByteIdx {
+        let comment = string.find(" #").unwrap_or(string.len());
+        if self.state.flow_frames.is_empty() {
+            comment
+        } else {
+            string
+                .find(|char: char| FLOW_DELIMITERS.contains(&char) || char == ':')
+                .map_or(comment, |idx| idx.min(comment))
+        }
+    }
+}
+
+impl SyntaxHighlighter for YamlSyntaxHighlighter {
+    fn highlight(&mut self, idx: LineIdx, line: &Line) {
+        debug_assert_eq!(idx, self.highlights.len());
+        let state = self.state.clone();
+        let mut result = Vec::new();
+        let mut errors = Vec::new();
+
+        let content = line.trim_start();
+        let indent = line.len().saturating_sub(content.len());
+
+        if !self.annotate_block_scalar(line, indent, &mut result) {
+            if line[..indent].contains('\t') {
+                self.report(
+                    idx,
+                    &mut errors,
+                    0,
+                    indent,
+                    "Tabs are not allowed in indentation".to_string(),
+                );
+            }
+
+            let mut pos = indent;
+            if self.state.flow_frames.is_empty()
+                && (content.starts_with("---") || content.starts_with("..."))
+                && content[3..].chars().next().is_none_or(char::is_whitespace)
+            {
+                result.push(Annotation {
+                    annotation_type: AnnotationType::Keyword,
+                    start: pos,
+                    end: pos.saturating_add(3),
+                });
+                pos = pos.saturating_add(3);
+            }
+            while line[pos..].starts_with("- ") || &line[pos..] == "-" {
+                pos = pos.saturating_add(1);
+                pos = line.len().saturating_sub(line[pos..].trim_start().len());
+            }
+            pos = self.annotate_key(line, pos, &mut result);
+            self.annotate_values(idx, line, indent, pos, &mut result, &mut errors);
+        }
+
+        result.append(&mut errors);
+        self.highlights.push(state, result);
+    }
+
+    fn get_annotations(&self, idx: LineIdx) -> Option<&Vec<Annotation>> {
+        self.highlights.get(idx)
+    }
+
+    fn finish(&mut self) {
+        if !self.highlights.finish() {
+            return;
+        }
+        for frame in &self.state.flow_frames {
+            self.highlights.report_unclosed(
+                frame.line_idx,
+                frame.start,
+                format!("Unclosed '{}'", frame.bracket),
+            );
+        }
+    }
+
+    fn get_diagnostic(&self, idx: LineIdx) -> Option<&str> {
+        self.highlights.diagnostic(idx)
+    }
+
+    fn rewind(&mut self, idx: LineIdx) {
+        if let Some(state) = self.highlights.rewind(idx) {
+            self.state = state;
+        }
+    }
+
+    fn highlighted_len(&self) -> LineIdx {
+        self.highlights.len()
+    }
+}
+
+fn find_mapping_colon(string: &str) -> Option<ByteIdx> {
+    let comment = string.find(" #").unwrap_or(string.len());
+    string[..comment]
+        .match_indices(':')
+        .map(|(idx, _)| idx)
+        .find(|idx| {
+            string[idx.saturating_add(1)..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+        })
+        .filter(|idx| *idx > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(lines: &[&str]) -> YamlSyntaxHighlighter {
+        let mut highlighter = YamlSyntaxHighlighter::default();
+        for (idx, line) in lines.iter().enumerate() {
+            highlighter.highlight(idx, &Line::from(line));
+        }
+        highlighter.finish();
+        highlighter
+    }
+
+    fn annotated<'a>(highlighter: &YamlSyntaxHighlighter, lines: &[&'a str], idx: LineIdx) -> Vec<(AnnotationType, &'a str)> {
+        highlighter
+            .get_annotations(idx)
+            .into_iter()
+            .flatten()
+            .map(|annotation| (annotation.annotation_type, &lines[idx][annotation.start..annotation.end]))
+            .collect()
+    }
+
+    #[test]
+    fn values() {
+        let lines = [
+            "---",
+            "name: hecto # the name",
+            "size: 1.5",
+            "at: 12:30",
+            "- enabled: yes",
+            "  tags: [a, 'b']",
+            "anchor: &base ~",
+        ];
+        let highlighter = highlight(&lines);
+        assert_eq!(annotated(&highlighter, &lines, 0), [(AnnotationType::Keyword, "---")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 1),
+            [(AnnotationType::Key, "name"), (AnnotationType::Comment, "# the name")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [(AnnotationType::Key, "size"), (AnnotationType::Number, "1.5")]
+        );
+        assert_eq!(annotated(&highlighter, &lines, 3), [(AnnotationType::Key, "at")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 4),
+            [(AnnotationType::Key, "enabled"), (AnnotationType::KnownValue, "yes")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 5),
+            [(AnnotationType::Key, "tags"), (AnnotationType::String, "'b'")]
+        );
+        assert_eq!(
+            annotated(&highlighter, &lines, 6),
+            [
+                (AnnotationType::Key, "anchor"),
+                (AnnotationType::Type, "&base"),
+                (AnnotationType::KnownValue, "~"),
+            ]
+        );
+        assert!((0..lines.len()).all(|idx| highlighter.get_diagnostic(idx).is_none()));
+    }
+
+    #[test]
+    fn block_scalars() {
+        let lines = ["script: |", "  echo: [", "done: true"];
+        let highlighter = highlight(&lines);
+        assert_eq!(annotated(&highlighter, &lines, 1), [(AnnotationType::String, "echo: [")]);
+        assert_eq!(
+            annotated(&highlighter, &lines, 2),
+            [(AnnotationType::Key, "done"), (AnnotationType::KnownValue, "true")]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let highlighter = highlight(&["a: [1, 2}", "\tb: 1", "c: 'open", "d: {", "e: 1"]);
+        assert_eq!(
+            highlighter.get_diagnostic(0),
+            Some("Mismatched '}': '[' from line 1 is still open")
+        );
+        assert_eq!(highlighter.get_diagnostic(1), Some("Tabs are not allowed in indentation"));
+        assert_eq!(highlighter.get_diagnostic(2), Some("Unterminated string"));
+        assert_eq!(highlighter.get_diagnostic(3), Some("Unclosed '{'"));
+    }
+
+    #[test]
+    fn rewinding_takes_back_unclosed_brackets() {
+        let mut highlighter = highlight(&["a: [", "  1,"]);
+        assert_eq!(highlighter.get_diagnostic(0), Some("Unclosed '['"));
+
+        highlighter.rewind(2);
+        assert_eq!(highlighter.get_diagnostic(0), None);
+        highlighter.highlight(2, &Line::from("]"));
+        highlighter.finish();
+        assert_eq!(highlighter.get_diagnostic(0), None);
+        assert_eq!(annotated(&highlighter, &["", "  1,"], 1), [(AnnotationType::Number, "1")]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,655 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+};
+
+use super::super::super::{Encoding, LineEnding};
+use super::atomicwrite::write_atomically_with;
+use super::Line;
+use crate::prelude::*;
+
+/// Files bigger than this open as a `LargeFile` unless configured otherwise.
+pub const DEFAULT_LARGE_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// How much of a file is looked at to tell its encoding, and whether it is
+/// text at all, before it is opened as a `LargeFile`.
+pub const SAMPLE_LEN: u64 = 64 * 1024;
+const INDEX_CHUNK_LEN: usize = 1024 * 1024;
+// How many lines around the requested ones are read along with them, so
+// that scrolling a little does not go back to the disk.
+const WINDOW_MARGIN: usize = 256;
+// Longer lines are cut off; nobody reads a megabyte of one line on screen.
+const MAX_LINE_LEN: u64 = 1024 * 1024;
+// How many lines a search or a save reads from disk at a time.
+const CHUNK_LINES: usize = 4096;
+// The lines of the file as it was opened; the end is that of the index.
+const ALL_LINES: Range<LineIdx> = 0..LineIdx::MAX;
+
+/// Where each line starts, filled in by a background thread.
+#[derive(Default)]
+struct LineIndex {
+    starts: Mutex<Vec<u64>>,
+    indexed_len: AtomicU64,
+    done: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+/// A run of lines of the document: lines of the file as it was opened,
+/// given by their indices, or lines edited or added since.
+enum Piece {
+    Original(Range<LineIdx>),
+    Edited(Vec<(Line, LineEnding)>),
+}
+
+impl Piece {
+    fn len(&self, original_line_count: usize) -> usize {
+        match self {
+            Self::Original(lines) => lines.end.min(original_line_count).saturating_sub(lines.start),
+            Self::Edited(lines) => lines.len(),
+        }
+    }
+}
+
+/// A file too big to read into memory up front. Its lines are indexed in
+/// the background, and only those around what is on screen are read. Once
+/// indexed it can be edited: the document is then a list of pieces, runs
+/// of unchanged lines read from the file and runs of edited ones held in
+/// memory, and saving streams them to disk.
+pub struct LargeFile {
+    file: File,
+    encoding: Encoding,
+    len: u64,
+    index: Arc<LineIndex>,
+    pieces: Vec<Piece>,
+    // Lines of the file as it was opened, from `window_start` on.
+    window_start: LineIdx,
+    window: Vec<Line>,
+}
+
+impl LargeFile {
+    /// Starts indexing the file at `path`, which holds text in `encoding`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(path: &Path, encoding: Encoding) -> Result<Self, Error> {
+        // The indexer reads through its own handle, so that reading lines
+        // does not move it around the file.
+        let to_index = File::open(path)?;
+        let len = to_index.metadata()?.len();
+        let index = Arc::new(LineIndex::default());
+        let shared = Arc::clone(&index);
+        thread::spawn(move || index_lines(to_index, &shared));
+        Ok(Self {
+            file: File::open(path)?,
+            encoding,
+            len,
+            index,
+            pieces: vec![Piece::Original(ALL_LINES)],
+            window_start: 0,
+            window: Vec::new(),
+        })
+    }
+
+    /// How far indexing has got, in percent, or `None` once it is done.
+    #[must_use]
+    pub fn progress(&self) -> Option<u8> {
+        if self.index.done.load(Ordering::Acquire) {
+            return None;
+        }
+        let percent = self
+            .indexed_len()
+            .saturating_mul(100)
+            .checked_div(self.len)
+            .unwrap_or(0);
+        Some(u8::try_from(percent.min(99)).unwrap_or(99))
+    }
+
+    /// The number of lines of the document known so far.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        let original_line_count = self.original_line_count();
+        self.pieces
+            .iter()
+            .map(|piece| piece.len(original_line_count))
+            .fold(0, usize::saturating_add)
+    }
+
+    #[must_use]
+    pub fn line(&self, idx: LineIdx) -> Option<&Line> {
+        let (piece_idx, offset) = self.locate(idx)?;
+        match self.pieces.get(piece_idx)? {
+            Piece::Original(lines) => self
+                .window
+                .get(lines.start.saturating_add(offset).checked_sub(self.window_start)?),
+            Piece::Edited(lines) => lines.get(offset).map(|(line, _)| line),
+        }
+    }
+
+    /// The line at `idx` with its line break, for editing. Only a file that
+    /// is fully indexed can be edited.
+    pub fn line_mut(&mut self, idx: LineIdx) -> Option<&mut (Line, LineEnding)> {
+        let (piece_idx, offset) = self.make_edited(idx)?;
+        match self.pieces.get_mut(piece_idx)? {
+            Piece::Edited(lines) => lines.get_mut(offset),
+            Piece::Original(_) => None,
+        }
+    }
+
+    /// Inserts `line` before the line at `idx`, or after the last one.
+    pub fn insert(&mut self, idx: LineIdx, line: (Line, LineEnding)) {
+        // New lines join the piece of the line before them, which is
+        // usually the one that was just split.
+        let at = match idx.checked_sub(1) {
+            Some(previous) => self
+                .make_edited(previous)
+                .map(|(piece_idx, offset)| (piece_idx, offset.saturating_add(1))),
+            None => self.make_edited(0),
+        };
+        if let Some((piece_idx, offset)) = at {
+            if let Some(Piece::Edited(lines)) = self.pieces.get_mut(piece_idx) {
+                lines.insert(offset, line);
+            }
+        }
+    }
+
+    /// Removes the line at `idx` and returns it.
+    pub fn remove(&mut self, idx: LineIdx) -> Option<(Line, LineEnding)> {
+        let (piece_idx, offset) = self.make_edited(idx)?;
+        let Some(Piece::Edited(lines)) = self.pieces.get_mut(piece_idx) else {
+            return None;
+        };
+        let removed = lines.remove(offset);
+        if lines.is_empty() {
+            self.pieces.remove(piece_idx);
+        }
+        Some(removed)
+    }
+
+    /// Makes sure the lines in `range` can be had from `line`, reading them
+    /// and some around them if they are not at hand.
+    pub fn load(&mut self, range: Range<LineIdx>) {
+        let Some(original) = self.original_lines_in(range) else {
+            return;
+        };
+        let window_end = self.window_start.saturating_add(self.window.len());
+        if original.start >= self.window_start && original.end <= window_end {
+            return;
+        }
+        let start = original.start.saturating_sub(WINDOW_MARGIN);
+        let end = original
+            .end
+            .saturating_add(WINDOW_MARGIN)
+            .min(self.original_line_count());
+        self.window = self
+            .read_original(start..end)
+            .map(|texts| texts.iter().map(|text| Line::from(text)).collect())
+            .unwrap_or_default();
+        self.window_start = start;
+    }
+
+    /// Like `Buffer::search_forward`, reading through the file a chunk at a
+    /// time from `from`. A search that fails to read the file finds
+    /// nothing.
+    #[must_use]
+    pub fn search_forward(&self, query: &str, from: Location) -> Option<Location> {
+        let line_count = self.line_count();
+        let from_line_idx = from.line_idx.min(line_count.checked_sub(1)?);
+        // Wraps around to the top, ending on the line it started from.
+        let ranges = chunks(from_line_idx..line_count)
+            .chain(chunks(0..from_line_idx.saturating_add(1)));
+        let mut from_grapheme_idx = Some(from.grapheme_idx);
+        for range in ranges {
+            let texts = self.read_document(range.clone()).ok()?;
+            for (line_idx, text) in range.zip(texts) {
+                let start = from_grapheme_idx.take().unwrap_or(0);
+                if !text.contains(query) {
+                    continue;
+                }
+                let line = Line::from(&text);
+                if let Some(grapheme_idx) =
+                    line.search_forward(query, start.min(line.grapheme_count()))
+                {
+                    return Some(Location {
+                        grapheme_idx,
+                        line_idx,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Like `Buffer::search_backward`, reading through the file a chunk at a
+    /// time back from `from`.
+    #[must_use]
+    pub fn search_backward(&self, query: &str, from: Location) -> Option<Location> {
+        let line_count = self.line_count();
+        let from_line_idx = from.line_idx.min(line_count.checked_sub(1)?);
+        // Wraps around to the bottom, ending on the line it started from.
+        let ranges = chunks(0..from_line_idx.saturating_add(1))
+            .rev()
+            .chain(chunks(from_line_idx..line_count).rev());
+        let mut until = Some(from.grapheme_idx);
+        for range in ranges {
+            let texts = self.read_document(range.clone()).ok()?;
+            for (line_idx, text) in range.zip(texts).rev() {
+                let end = until.take();
+                if !text.contains(query) {
+                    continue;
+                }
+                let line = Line::from(&text);
+                let end = end.map_or(line.grapheme_count(), |end| end.min(line.grapheme_count()));
+                if let Some(grapheme_idx) = line.search_backward(query, end) {
+                    return Some(Location {
+                        grapheme_idx,
+                        line_idx,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Writes the document to `out` in `encoding`. Unchanged lines are
+    /// copied as they are if the encoding stays the same.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, `out` cannot be written
+    /// or the text cannot be encoded.
+    pub fn write_to(&self, out: &mut impl Write, encoding: Encoding) -> Result<(), Error> {
+        let copies = encoding == self.encoding;
+        let starts_with_original_bom =
+            copies && matches!(self.pieces.first(), Some(Piece::Original(lines)) if lines.start == 0);
+        if encoding == Encoding::Utf8Bom && !starts_with_original_bom {
+            out.write_all(&encoding.encode("")?)?;
+        }
+        let original_line_count = self.original_line_count();
+        let ends_with_newline = self.ends_with_newline();
+        let mut pieces = self.pieces.iter().peekable();
+        while let Some(piece) = pieces.next() {
+            let is_last = pieces.peek().is_none();
+            match piece {
+                Piece::Original(lines) => {
+                    let end = lines.end.min(original_line_count);
+                    for range in chunks(lines.start..end) {
+                        let bytes = self.read_original_bytes(range)?;
+                        if copies {
+                            out.write_all(&bytes)?;
+                        } else {
+                            out.write_all(&encode(&self.encoding.decode(&bytes), encoding)?)?;
+                        }
+                    }
+                    // The last line of the file may have had no break for
+                    // other lines to follow.
+                    if end == original_line_count && !ends_with_newline && !is_last {
+                        out.write_all(&encode(LineEnding::default().as_str(), encoding)?)?;
+                    }
+                }
+                Piece::Edited(lines) => {
+                    for (idx, (line, line_ending)) in lines.iter().enumerate() {
+                        out.write_all(&encode(line, encoding)?)?;
+                        if !is_last || idx.saturating_add(1) < lines.len() || ends_with_newline {
+                            out.write_all(&encode(line_ending.as_str(), encoding)?)?;
+                        }
+                    }
+                }
+            }
+        }
+        out.flush()
+    }
+
+    /// Writes the document to `path` and goes on reading it from there, so
+    /// that the edits no longer need to be held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or written.
+    pub fn save(&mut self, path: &Path) -> Result<(), Error> {
+        if self.progress().is_some() {
+            return Err(Error::new(ErrorKind::WouldBlock, "the file is still being indexed"));
+        }
+        let mut starts = Vec::new();
+        let mut len = 0;
+        write_atomically_with(path, |file| {
+            let mut out = IndexingWriter::new(BufWriter::new(file));
+            self.write_to(&mut out, self.encoding)?;
+            (starts, len) = (out.starts, out.len);
+            Ok(())
+        })?;
+        self.file = File::open(path)?;
+        self.len = len;
+        *self.starts() = starts;
+        self.index.indexed_len.store(len, Ordering::Release);
+        self.pieces = vec![Piece::Original(ALL_LINES)];
+        self.window_start = 0;
+        self.window.clear();
+        Ok(())
+    }
+
+    /// Where the line at `idx` is: the index of its piece and its offset
+    /// within it.
+    fn locate(&self, idx: LineIdx) -> Option<(usize, usize)> {
+        let original_line_count = self.original_line_count();
+        let mut start: LineIdx = 0;
+        for (piece_idx, piece) in self.pieces.iter().enumerate() {
+            let end = start.saturating_add(piece.len(original_line_count));
+            if idx < end {
+                return Some((piece_idx, idx.saturating_sub(start)));
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// Like `locate`, first moving an unchanged line into an edited piece
+    /// of its own, or onto the end of the edited piece right before it.
+    fn make_edited(&mut self, idx: LineIdx) -> Option<(usize, usize)> {
+        if self.progress().is_some() {
+            return None;
+        }
+        let (piece_idx, offset) = self.locate(idx)?;
+        let Some(Piece::Original(lines)) = self.pieces.get(piece_idx) else {
+            return Some((piece_idx, offset));
+        };
+        let original_idx = lines.start.saturating_add(offset);
+        let before = lines.start..original_idx;
+        let after = original_idx.saturating_add(1)..lines.end;
+        let line = self.read_original_line(original_idx).ok()?;
+
+        let previous_idx = piece_idx.checked_sub(1);
+        if before.is_empty() {
+            if let Some(Piece::Edited(previous)) =
+                previous_idx.and_then(|previous_idx| self.pieces.get_mut(previous_idx))
+            {
+                previous.push(line);
+                let at = (piece_idx.saturating_sub(1), previous.len().saturating_sub(1));
+                self.replace_piece(piece_idx, [Piece::Original(after)]);
+                return Some(at);
+            }
+        }
+        let edited_idx = piece_idx.saturating_add(usize::from(!before.is_empty()));
+        self.replace_piece(
+            piece_idx,
+            [
+                Piece::Original(before),
+                Piece::Edited(vec![line]),
+                Piece::Original(after),
+            ],
+        );
+        Some((edited_idx, 0))
+    }
+
+    /// Puts `pieces` in place of the piece at `idx`, leaving out empty ones.
+    fn replace_piece<const N: usize>(&mut self, idx: usize, pieces: [Piece; N]) {
+        let original_line_count = self.original_line_count();
+        let end = idx.saturating_add(1).min(self.pieces.len());
+        self.pieces.splice(
+            idx..end,
+            pieces
+                .into_iter()
+                .filter(|piece| piece.len(original_line_count) > 0),
+        );
+    }
+
+    /// The lines of the file as it was opened that `range` of the document
+    /// spans, if any.
+    fn original_lines_in(&self, range: Range<LineIdx>) -> Option<Range<LineIdx>> {
+        let original_line_count = self.original_line_count();
+        let mut spanned: Option<Range<LineIdx>> = None;
+        let mut start: LineIdx = 0;
+        for piece in &self.pieces {
+            let end = start.saturating_add(piece.len(original_line_count));
+            if let Piece::Original(lines) = piece {
+                let from = range.start.max(start);
+                let to = range.end.min(end);
+                if from < to {
+                    let first = lines.start.saturating_add(from.saturating_sub(start));
+                    let last = lines.start.saturating_add(to.saturating_sub(start));
+                    spanned = Some(spanned.map_or(first..last, |spanned| spanned.start..last));
+                }
+            }
+            if end >= range.end {
+                break;
+            }
+            start = end;
+        }
+        spanned
+    }
+
+    /// The text of the lines in `range` of the document.
+    fn read_document(&self, range: Range<LineIdx>) -> Result<Vec<String>, Error> {
+        let original_line_count = self.original_line_count();
+        let mut texts = Vec::with_capacity(range.len());
+        let mut start: LineIdx = 0;
+        for piece in &self.pieces {
+            let end = start.saturating_add(piece.len(original_line_count));
+            let from = range.start.max(start).saturating_sub(start);
+            let to = range.end.min(end).saturating_sub(start);
+            if from < to {
+                match piece {
+                    Piece::Original(lines) => texts.extend(self.read_original(
+                        lines.start.saturating_add(from)..lines.start.saturating_add(to),
+                    )?),
+                    Piece::Edited(lines) => texts.extend(
+                        lines
+                            .get(from..to)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|(line, _)| line.to_string()),
+                    ),
+                }
+            }
+            if end >= range.end {
+                break;
+            }
+            start = end;
+        }
+        Ok(texts)
+    }
+
+    /// The text of the lines in `range` of the file as it was opened.
+    fn read_original(&self, range: Range<LineIdx>) -> Result<Vec<String>, Error> {
+        let mut texts = Vec::with_capacity(range.len());
+        self.read_original_lines(range, |bytes| {
+            texts.push(self.encoding.decode(trim_line_ending(bytes)));
+        })?;
+        Ok(texts)
+    }
+
+    /// Line `idx` of the file as it was opened, with its line break.
+    fn read_original_line(&self, idx: LineIdx) -> Result<(Line, LineEnding), Error> {
+        let mut line = None;
+        self.read_original_lines(idx..idx.saturating_add(1), |bytes| {
+            let line_ending = if bytes.ends_with(b"\r\n") {
+                LineEnding::CrLf
+            } else {
+                LineEnding::Lf
+            };
+            let text = self.encoding.decode(trim_line_ending(bytes));
+            line = Some((Line::from(&text), line_ending));
+        })?;
+        line.ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "no such line"))
+    }
+
+    /// Hands the lines in `range` of the file as it was opened to `visit`,
+    /// with their line breaks.
+    fn read_original_lines(
+        &self,
+        range: Range<LineIdx>,
+        mut visit: impl FnMut(&[u8]),
+    ) -> Result<(), Error> {
+        let starts = self.starts();
+        let Some(&first_start) = starts.get(range.start) else {
+            return Ok(());
+        };
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(first_start))?;
+        let mut bytes = Vec::new();
+        for line_idx in range {
+            let Some(&start) = starts.get(line_idx) else {
+                break;
+            };
+            let end = starts
+                .get(line_idx.saturating_add(1))
+                .copied()
+                .unwrap_or_else(|| self.indexed_len());
+            let len = end.saturating_sub(start);
+            bytes.clear();
+            (&mut reader).take(len.min(MAX_LINE_LEN)).read_to_end(&mut bytes)?;
+            if len > MAX_LINE_LEN {
+                reader.seek(SeekFrom::Start(end))?;
+            }
+            visit(&bytes);
+        }
+        Ok(())
+    }
+
+    /// The bytes of the lines in `range` of the file as it was opened.
+    fn read_original_bytes(&self, range: Range<LineIdx>) -> Result<Vec<u8>, Error> {
+        let (start, end) = {
+            let starts = self.starts();
+            let start = starts.get(range.start).copied().unwrap_or_else(|| self.indexed_len());
+            let end = starts.get(range.end).copied().unwrap_or_else(|| self.indexed_len());
+            (start, end)
+        };
+        let mut bytes = Vec::new();
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(start))?;
+        file.take(end.saturating_sub(start)).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// The number of lines of the file as it was opened indexed so far. A
+    /// line counts once its end is known.
+    fn original_line_count(&self) -> usize {
+        let starts = self.starts();
+        if !self.index.done.load(Ordering::Acquire) {
+            return starts.len().saturating_sub(1);
+        }
+        // A final line break does not start another line.
+        starts
+            .len()
+            .saturating_sub(usize::from(self.ends_with_newline_in(&starts)))
+    }
+
+    fn ends_with_newline(&self) -> bool {
+        self.ends_with_newline_in(&self.starts())
+    }
+
+    fn ends_with_newline_in(&self, starts: &[u64]) -> bool {
+        let len = self.indexed_len();
+        len > 0 && starts.last() == Some(&len)
+    }
+
+    /// How much of the file has been indexed. The file may have grown since
+    /// it was opened.
+    fn indexed_len(&self) -> u64 {
+        self.index.indexed_len.load(Ordering::Acquire)
+    }
+
+    fn starts(&self) -> MutexGuard<'_, Vec<u64>> {
+        self.index.starts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for LargeFile {
+    fn drop(&mut self) {
+        self.index.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Passes what is written on to `inner`, noting where each line starts the
+/// way `index_lines` does.
+struct IndexingWriter<W> {
+    inner: W,
+    len: u64,
+    starts: Vec<u64>,
+}
+
+impl<W> IndexingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            len: 0,
+            starts: vec![0],
+        }
+    }
+}
+
+impl<W: Write> Write for IndexingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let written = self.inner.write(buf)?;
+        let written_bytes = buf.get(..written).unwrap_or_default();
+        self.starts.extend(line_starts(written_bytes, self.len));
+        self.len = self.len.saturating_add(u64::try_from(written).unwrap_or(0));
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+}
+
+fn index_lines(mut file: File, index: &LineIndex) {
+    index.starts.lock().unwrap_or_else(PoisonError::into_inner).push(0);
+    let mut chunk = vec![0; INDEX_CHUNK_LEN];
+    let mut offset: u64 = 0;
+    while !index.cancelled.load(Ordering::Relaxed) {
+        let Ok(read_len) = file.read(&mut chunk) else {
+            break;
+        };
+        if read_len == 0 {
+            break;
+        }
+        let new_starts = line_starts(chunk.get(..read_len).unwrap_or_default(), offset);
+        index
+            .starts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(new_starts);
+        offset = offset.saturating_add(u64::try_from(read_len).unwrap_or(0));
+        index.indexed_len.store(offset, Ordering::Release);
+    }
+    index.done.store(true, Ordering::Release);
+}
+
+/// Where the lines after the line feeds in `bytes` start, for `bytes` found
+/// at `offset`.
+fn line_starts(bytes: &[u8], offset: u64) -> impl Iterator<Item = u64> + '_ {
+    bytes
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+        .filter_map(|(idx, _)| u64::try_from(idx.saturating_add(1)).ok())
+        .map(move |next| offset.saturating_add(next))
+}
+
+/// `range` cut into runs of at most `CHUNK_LINES` lines.
+fn chunks(range: Range<LineIdx>) -> impl DoubleEndedIterator<Item = Range<LineIdx>> {
+    let end = range.end;
+    range
+        .step_by(CHUNK_LINES)
+        .map(move |start| start..start.saturating_add(CHUNK_LINES).min(end))
+}
+
+/// `text` in `encoding`, as part of a longer text: without a byte order
+/// mark.
+fn encode(text: &str, encoding: Encoding) -> Result<Vec<u8>, Error> {
+    match encoding {
+        Encoding::Utf8Bom => Encoding::Utf8.encode(text),
+        _ => encoding.encode(text),
+    }
+}
+
+fn trim_line_ending(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes.strip_suffix(b"\r").unwrap_or(bytes)
+}
\ No newline at end of file
@@ -1,20 +1,33 @@
-use std::{cmp::min, io::Error};
+use std::{
+    cmp::{min, Ordering},
+    collections::VecDeque,
+    io::{Error, Write},
+    mem,
+    ops::RangeInclusive,
+};
 use crate::editor::RowIdx;
 use crate::prelude::*;
 
 use super::super::{
     command::{Edit, Move},
-    DocumentStatus, Line, Terminal,
+    Backup, DocumentStatus, Encoding, FileOptions, FileType, IndentationOptions, Line, LineEnding, Terminal, ThemeElement,
 };
 use super::UIComponent;
+mod atomicwrite;
 mod buffer;
-use buffer::Buffer;
+mod diskstate;
+mod hexdump;
+use hexdump::BYTES_PER_ROW;
+mod largefile;
+use largefile::DEFAULT_LARGE_FILE_SIZE;
+mod swapfile;
+pub use buffer::Buffer;
 mod searchdirection;
 use searchdirection::SearchDirection;
 mod highlighter;
-use highlighter::Highlighter;
+pub use highlighter::{create_syntax_highlight, Highlighter, SyntaxHighlighter};
 mod fileinfo;
-use fileinfo::FileInfo;
+pub use fileinfo::FileInfo;
 mod searchinfo;
 use searchinfo::SearchInfo;
 
@@ -25,7 +38,22 @@ pub struct View {
     size: Size,
     text_location: Location,
     scroll_offset: Position,
+    selection_anchor: Option<Location>,
+    // Whether the high half of the byte at the caret of a binary file was
+    // just typed, so the next hex digit goes into the low half.
+    hex_nibble: bool,
+    indentation_options: IndentationOptions,
+    backup: Backup,
+    // Files bigger than this open as large files, `DEFAULT_LARGE_FILE_SIZE`
+    // unless configured.
+    large_file_size: Option<u64>,
     search_info: Option<SearchInfo>,
+    // The document and its caret while a diff is shown in its place.
+    stashed_buffer: Option<(Buffer, Location)>,
+    // The other open documents with their carets, in the order they follow
+    // the current one, which is number `buffer_idx` of all.
+    other_buffers: VecDeque<(Buffer, Location)>,
+    buffer_idx: usize,
 }
 
 impl View {
@@ -34,17 +62,36 @@ impl View {
         DocumentStatus {
             total_lines: self.buffer.height(),
             current_line_idx: self.text_location.line_idx,
-            file_name: format!("file_info"),
+            file_name: format!("{file_info}"),
             is_modified: self.buffer.is_dirty(),
+            is_read_only: self.buffer.is_read_only(),
+            buffer_idx: self.buffer_idx,
+            buffer_count: self.buffer_count(),
             file_type: file_info.get_file_type(),
+            line_ending: self.buffer.line_ending(),
+            encoding: self.buffer.encoding(),
+            is_binary: self.buffer.is_binary(),
+            total_bytes: self.buffer.byte_count(),
+            current_byte_idx: self.byte_idx(),
+            loading_progress: self.buffer.loading_progress(),
         }
     }
 
+    pub fn get_diagnostic(&self) -> Option<&str> {
+        self.buffer.get_diagnostic(self.text_location.line_idx)
+    }
+
+    #[cfg(test)]
+    pub fn line_text(&self, idx: LineIdx) -> Option<String> {
+        self.buffer.line(idx).map(ToString::to_string)
+    }
+
     pub const fn is_file_loaded(&self) -> bool {
         self.buffer.is_file_loaded()
     }
 
     pub fn enter_search(&mut self) {
+        self.clear_selection();
         self.search_info = Some(SearchInfo {
             prev_location: self.text_location,
             prev_scroll_offset: self.scroll_offset,
@@ -98,6 +145,7 @@ impl View {
             }
         }) {
             self.text_location = location;
+            self.load_caret_line();
             self.center_text_location();
         };
         self.set_needs_redraw(true);
@@ -120,54 +168,472 @@ impl View {
         self.search_in_direction(self.text_location, SearchDirection::Backward);
     }
 
-    pub fn load(&mut self, file_name: &str) -> Result<(), Error> {
-        let buffer = Buffer::load(file_name)?;
-        self.buffer = buffer;
-        self.set_needs_redraw(true);
+    /// Opens `file_name` as the current document, or behind the others if
+    /// one is open already.
+    pub fn load(&mut self, file_name: &str, options: FileOptions) -> Result<(), Error> {
+        self.add_buffer(Buffer::load(file_name, self.large_file_size())?, options);
         Ok(())
     }
 
+    /// Like `load`, for contents that do not come from a file.
+    pub fn load_bytes(&mut self, bytes: Vec<u8>, options: FileOptions) {
+        self.add_buffer(Buffer::from_bytes(bytes), options);
+    }
+
+    fn add_buffer(&mut self, mut buffer: Buffer, options: FileOptions) {
+        buffer.set_indentation_options(self.indentation_options);
+        buffer.set_backup(self.backup.clone());
+        if let Some(file_type) = options.file_type {
+            buffer.set_file_type(file_type);
+        }
+        if options.read_only {
+            buffer.set_read_only(true);
+        }
+
+        // The empty document the editor starts with makes way.
+        let is_placeholder = !self.buffer.is_file_loaded()
+            && self.buffer.is_empty()
+            && !self.buffer.is_dirty()
+            && self.other_buffers.is_empty();
+        if is_placeholder {
+            self.buffer = buffer;
+            self.go_to(options.at);
+        } else {
+            let last = self.other_buffers.len().saturating_sub(self.buffer_idx);
+            self.other_buffers.insert(last, (buffer, options.at));
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub fn buffer_count(&self) -> usize {
+        self.other_buffers.len().saturating_add(1)
+    }
+
+    /// Makes the next open document, or the previous one, the current one.
+    pub fn switch_buffer(&mut self, forward: bool) {
+        self.hide_diff();
+        let next = if forward {
+            self.other_buffers.pop_front()
+        } else {
+            self.other_buffers.pop_back()
+        };
+        let Some((buffer, location)) = next else {
+            return;
+        };
+        let current = (mem::replace(&mut self.buffer, buffer), self.text_location);
+        let count = self.buffer_count().saturating_add(1);
+        self.buffer_idx = if forward {
+            self.other_buffers.push_back(current);
+            self.buffer_idx.saturating_add(1)
+        } else {
+            self.other_buffers.push_front(current);
+            self.buffer_idx.saturating_add(count).saturating_sub(1)
+        }
+        .checked_rem(count)
+        .unwrap_or(0);
+        self.go_to(location);
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.buffer.is_dirty() || self.has_unsaved_changes_in_background()
+    }
+
+    pub fn has_unsaved_changes_in_background(&self) -> bool {
+        self.other_buffers.iter().any(|(buffer, _)| buffer.is_dirty())
+    }
+
+    /// Writes the current buffer to `out`, as it would be saved.
+    pub fn write_text(&self, out: &mut impl Write) -> Result<(), Error> {
+        self.buffer.write_contents(out)
+    }
+
+    pub fn remove_swap_files(&self) {
+        self.buffer.remove_swap_file();
+        for (buffer, _) in &self.other_buffers {
+            buffer.remove_swap_file();
+        }
+    }
+
+    fn go_to(&mut self, location: Location) {
+        self.text_location = location;
+        self.selection_anchor = None;
+        if self.buffer.is_binary() {
+            self.go_to_byte(self.byte_idx());
+        } else {
+            self.snap_to_valid_line();
+            self.load_caret_line();
+            self.snap_to_valid_grapheme();
+        }
+        self.center_text_location();
+    }
+
     pub fn save(&mut self) -> Result<(), Error> {
         self.buffer.save()?;
+        // Saving may have trimmed trailing whitespace under the caret.
+        self.snap_to_valid_grapheme();
         self.set_needs_redraw(true);
         Ok(())
     }
 
     pub fn save_as(&mut self, file_name: &str) -> Result<(), Error> {
         self.buffer.save_as(file_name)?;
+        self.snap_to_valid_grapheme();
+        self.set_needs_redraw(true);
+        Ok(())
+    }
+
+    pub fn changed_on_disk(&mut self) -> bool {
+        self.buffer.changed_on_disk()
+    }
+
+    pub fn has_unseen_disk_change(&mut self) -> bool {
+        self.buffer.has_unseen_disk_change()
+    }
+
+    pub fn dismiss_disk_change(&mut self) {
+        self.buffer.dismiss_disk_change();
+    }
+
+    /// Replaces the document with the file's current contents, keeping the
+    /// caret where it was as far as the new contents allow.
+    pub fn reload(&mut self) -> Result<(), Error> {
+        self.hide_diff();
+        let file_info = self.buffer.get_file_info();
+        let Some(path) = file_info.get_path() else {
+            return Ok(());
+        };
+        let file_type = file_info.get_file_type();
+        let mut buffer = Buffer::load(&path.to_string_lossy(), self.large_file_size())?;
+        self.buffer.remove_swap_file();
+        buffer.set_indentation_options(self.indentation_options);
+        buffer.set_backup(self.backup.clone());
+        buffer.set_file_type(file_type);
+        buffer.set_read_only(self.buffer.is_read_only());
+        self.buffer = buffer;
+        self.selection_anchor = None;
+        self.snap_to_valid_line();
+        self.snap_to_valid_grapheme();
+        self.scroll_text_location_into_view();
         self.set_needs_redraw(true);
         Ok(())
     }
 
+    /// Shows the changes saving would make to the file on disk in place of
+    /// the document, until `hide_diff` is called.
+    pub fn show_disk_diff(&mut self) -> Result<(), Error> {
+        if self.stashed_buffer.is_none() {
+            self.show_diff(&self.buffer.diff_with_disk()?);
+        }
+        Ok(())
+    }
+
+    /// Like `show_disk_diff`, for the changes recovering the swap file
+    /// would make.
+    pub fn show_swap_diff(&mut self) -> Result<(), Error> {
+        if self.stashed_buffer.is_none() {
+            self.show_diff(&self.buffer.diff_with_swap_file()?);
+        }
+        Ok(())
+    }
+
+    fn show_diff(&mut self, diff: &[String]) {
+        let diff = Buffer::from_lines(diff);
+        let buffer = mem::replace(&mut self.buffer, diff);
+        self.stashed_buffer = Some((buffer, self.text_location));
+        self.text_location = Location::default();
+        self.selection_anchor = None;
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn hide_diff(&mut self) {
+        if let Some((buffer, text_location)) = self.stashed_buffer.take() {
+            self.buffer = buffer;
+            self.text_location = text_location;
+            self.selection_anchor = None;
+            self.scroll_text_location_into_view();
+            self.set_needs_redraw(true);
+        }
+    }
+
+    pub fn write_swap_file(&self) -> Result<(), Error> {
+        self.buffer.write_swap_file()
+    }
+
+    pub fn remove_swap_file(&self) {
+        self.buffer.remove_swap_file();
+    }
+
+    pub fn keep_swap_file(&mut self) {
+        self.buffer.keep_swap_file();
+    }
+
+    pub fn has_swap_file(&self) -> bool {
+        self.buffer.has_swap_file()
+    }
+
+    pub fn recover_from_swap_file(&mut self) -> Result<(), Error> {
+        self.hide_diff();
+        self.buffer.recover_from_swap_file()?;
+        self.selection_anchor = None;
+        self.snap_to_valid_line();
+        self.snap_to_valid_grapheme();
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+        Ok(())
+    }
+
+    pub fn set_file_type(&mut self, file_type: FileType) {
+        self.buffer.set_file_type(file_type);
+        self.set_needs_redraw(true);
+    }
+
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.buffer.set_line_ending(line_ending);
+        self.set_needs_redraw(true);
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.buffer.set_encoding(encoding);
+        self.set_needs_redraw(true);
+    }
+
+    pub fn set_indentation_options(&mut self, options: IndentationOptions) {
+        self.indentation_options = options;
+        self.buffer.set_indentation_options(options);
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    pub const fn is_binary(&self) -> bool {
+        self.buffer.is_binary()
+    }
+
+    pub const fn is_read_only(&self) -> bool {
+        self.buffer.is_read_only()
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.buffer.set_read_only(read_only);
+    }
+
+    pub fn set_backup(&mut self, backup: Backup) {
+        self.buffer.set_backup(backup.clone());
+        self.backup = backup;
+    }
+
+    pub fn set_large_file_size(&mut self, size: u64) {
+        self.large_file_size = Some(size);
+    }
+
+    fn large_file_size(&self) -> u64 {
+        self.large_file_size.unwrap_or(DEFAULT_LARGE_FILE_SIZE)
+    }
+
+    pub const fn is_large_file(&self) -> bool {
+        self.buffer.is_large_file()
+    }
+
+    pub fn loading_progress(&self) -> Option<u8> {
+        self.buffer.loading_progress()
+    }
+
     pub fn handle_edit_command(&mut self, command: Edit) {
+        // Tab and Shift-Tab indent and dedent the selected lines, so they
+        // keep the selection.
+        if !matches!(command, Edit::Insert('\t') | Edit::Dedent) {
+            self.clear_selection();
+        }
+        if self.buffer.is_binary() {
+            self.handle_hex_edit_command(command);
+            self.scroll_text_location_into_view();
+            return;
+        }
         match command {
+            Edit::Insert('\t') if self.selection().is_some() => self.shift_lines(true),
+            Edit::Insert('\t') => {
+                self.clear_selection();
+                self.insert_tab();
+            }
             Edit::Insert(character) => self.insert_char(character),
+            Edit::Dedent => self.shift_lines(false),
             Edit::Delete => self.delete(),
             Edit::DeleteBackward => self.delete_backward(),
             Edit::InsertNewline => self.insert_newline(),
         }
+        self.scroll_text_location_into_view();
     }
 
     pub fn handle_move_command(&mut self, command: Move) {
         let Size { height, .. } = self.size;
+        let previous_line_idx = self.text_location.line_idx;
+        self.clear_selection();
 
-        match command {
-            Move::Up => self.move_up(1),
-            Move::Down => self.move_down(1),
-            Move::Left => self.move_left(),
-            Move::Right => self.move_right(),
-            Move::PageUp => self.move_up(height.saturating_sub(1)),
-            Move::PageDown => self.move_down(height.saturating_sub(1)),
-            Move::StartOfLine => self.move_to_start_of_line(),
-            Move::EndOfLine => self.move_to_end_of_line(),
+        if self.buffer.is_binary() {
+            self.handle_hex_move_command(command);
+        } else {
+            match command {
+                Move::Up => self.move_up(1),
+                Move::Down => self.move_down(1),
+                Move::Left => self.move_left(),
+                Move::Right => self.move_right(),
+                Move::PageUp => self.move_up(height.saturating_sub(1)),
+                Move::PageDown => self.move_down(height.saturating_sub(1)),
+                Move::StartOfLine => self.move_to_start_of_line(),
+                Move::EndOfLine => self.move_to_end_of_line(),
+            }
         }
 
+        if self.text_location.line_idx != previous_line_idx {
+            self.set_needs_redraw(true);
+        }
+        self.scroll_text_location_into_view();
+    }
+
+    pub fn click(&mut self, position: Position) {
+        let location = self.position_to_text_location(position);
+        self.text_location = location;
+        self.hex_nibble = false;
+        self.selection_anchor = Some(location);
         self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn drag_to(&mut self, position: Position) {
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.text_location);
+        }
+        self.text_location = self.position_to_text_location(position);
+        self.hex_nibble = false;
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn select_word_at(&mut self, position: Position) {
+        let location = self.position_to_text_location(position);
+        let bounds = self.buffer.word_bounds(location);
+        self.selection_anchor = Some(Location {
+            line_idx: location.line_idx,
+            grapheme_idx: bounds.start,
+        });
+        self.text_location = Location {
+            line_idx: location.line_idx,
+            grapheme_idx: bounds.end,
+        };
+        self.scroll_text_location_into_view();
+        self.set_needs_redraw(true);
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset.row = self.scroll_offset.row.saturating_sub(lines);
+        self.set_needs_redraw(true);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        let max_row = self.buffer.height().saturating_sub(self.size.height);
+        self.scroll_offset.row = min(self.scroll_offset.row.saturating_add(lines), max_row);
+        self.set_needs_redraw(true);
+    }
+
+    fn selection(&self) -> Option<(Location, Location)> {
+        let anchor = self.selection_anchor?;
+        let caret = self.text_location;
+        let anchor_key = (anchor.line_idx, anchor.grapheme_idx);
+        let caret_key = (caret.line_idx, caret.grapheme_idx);
+        match anchor_key.cmp(&caret_key) {
+            Ordering::Less => Some((anchor, caret)),
+            Ordering::Greater => Some((caret, anchor)),
+            Ordering::Equal => None,
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        if self.selection_anchor.take().is_some() {
+            self.set_needs_redraw(true);
+        }
+    }
+
+    fn position_to_text_location(&self, position: Position) -> Location {
+        if self.buffer.is_binary() {
+            let row_start = position
+                .row
+                .saturating_add(self.scroll_offset.row)
+                .saturating_mul(BYTES_PER_ROW);
+            let col = position.col.saturating_add(self.scroll_offset.col);
+            return self.byte_location(row_start.saturating_add(hexdump::byte_at_column(col)));
+        }
+        let line_idx = min(
+            position.row.saturating_add(self.scroll_offset.row),
+            self.buffer.height(),
+        );
+        let grapheme_idx = self
+            .buffer
+            .grapheme_idx_at(line_idx, position.col.saturating_add(self.scroll_offset.col));
+        Location {
+            grapheme_idx,
+            line_idx,
+        }
+    }
+
+    fn insert_tab(&mut self) {
+        let indentation = self.buffer.indentation();
+        if !indentation.expand_tabs {
+            self.insert_char('\t');
+            return;
+        }
+
+        let col = self.text_location_to_position().col;
+        let indent_size = indentation.indent_size.max(1);
+        let spaces = indent_size.saturating_sub(col.checked_rem(indent_size).unwrap_or(0));
+        for _ in 0..spaces {
+            self.insert_char(' ');
+        }
+    }
+
+    /// The lines touched by the selection, or the caret's line. A selection
+    /// ending at the start of a line does not include that line.
+    fn selected_lines(&self) -> RangeInclusive<LineIdx> {
+        match self.selection() {
+            Some((start, end)) => {
+                let last = if end.grapheme_idx == 0 && end.line_idx > start.line_idx {
+                    end.line_idx.saturating_sub(1)
+                } else {
+                    end.line_idx
+                };
+                start.line_idx..=last
+            }
+            None => self.text_location.line_idx..=self.text_location.line_idx,
+        }
+    }
+
+    fn shift_lines(&mut self, indent: bool) {
+        for line_idx in self.selected_lines() {
+            let delta = if indent {
+                self.buffer.indent_line(line_idx)
+            } else {
+                self.buffer.dedent_line(line_idx)
+            };
+            for location in [Some(&mut self.text_location), self.selection_anchor.as_mut()]
+                .into_iter()
+                .flatten()
+                .filter(|location| location.line_idx == line_idx)
+            {
+                location.grapheme_idx = if indent {
+                    location.grapheme_idx.saturating_add(delta)
+                } else {
+                    location.grapheme_idx.saturating_sub(delta)
+                };
+            }
+        }
+        self.set_needs_redraw(true);
     }
 
     fn insert_newline(&mut self) {
-        self.buffer.insert_newline(self.text_location);
-        self.handle_move_command(Move::Right);
+        let indent = self.buffer.insert_newline(self.text_location);
+        self.text_location = Location {
+            line_idx: self.text_location.line_idx.saturating_add(1),
+            grapheme_idx: indent,
+        };
         self.set_needs_redraw(true);
     }
 
@@ -184,6 +650,11 @@ impl View {
     }
 
     fn insert_char(&mut self, character: char) {
+        let dedent = self
+            .buffer
+            .dedent_before_closing(character, self.text_location);
+        self.text_location.grapheme_idx = self.text_location.grapheme_idx.saturating_sub(dedent);
+
         let old_len = self.buffer.grapheme_count(self.text_location.line_idx);
         self.buffer.insert_char(character, self.text_location);
 
@@ -198,6 +669,89 @@ impl View {
         self.set_needs_redraw(true);
     }
 
+    /// The offset of the byte at the caret of a binary file.
+    fn byte_idx(&self) -> ByteIdx {
+        self.text_location
+            .line_idx
+            .saturating_mul(BYTES_PER_ROW)
+            .saturating_add(self.text_location.grapheme_idx)
+    }
+
+    /// Where byte `idx` of a binary file is shown, or the last byte if the
+    /// file is shorter.
+    fn byte_location(&self, idx: ByteIdx) -> Location {
+        let idx = min(idx, self.buffer.byte_count().saturating_sub(1));
+        Location {
+            line_idx: idx.checked_div(BYTES_PER_ROW).unwrap_or(0),
+            grapheme_idx: idx.checked_rem(BYTES_PER_ROW).unwrap_or(0),
+        }
+    }
+
+    fn go_to_byte(&mut self, idx: ByteIdx) {
+        self.text_location = self.byte_location(idx);
+        self.hex_nibble = false;
+    }
+
+    fn handle_hex_move_command(&mut self, command: Move) {
+        let Size { height, .. } = self.size;
+        let idx = self.byte_idx();
+        let row_start = idx.saturating_sub(self.text_location.grapheme_idx);
+        let page = height.saturating_sub(1).saturating_mul(BYTES_PER_ROW);
+        self.go_to_byte(match command {
+            Move::Up => idx.checked_sub(BYTES_PER_ROW).unwrap_or(idx),
+            Move::Down => idx.saturating_add(BYTES_PER_ROW),
+            Move::Left => idx.saturating_sub(1),
+            Move::Right => idx.saturating_add(1),
+            Move::PageUp => idx.saturating_sub(page),
+            Move::PageDown => idx.saturating_add(page),
+            Move::StartOfLine => row_start,
+            Move::EndOfLine => row_start.saturating_add(BYTES_PER_ROW.saturating_sub(1)),
+        });
+    }
+
+    /// Hex digits overwrite the byte at the caret, high half first, then
+    /// move on to the next byte. Nothing else edits a binary file.
+    fn handle_hex_edit_command(&mut self, command: Edit) {
+        let Edit::Insert(character) = command else {
+            return;
+        };
+        let Some(nibble) = character
+            .to_digit(16)
+            .and_then(|digit| u8::try_from(digit).ok())
+        else {
+            return;
+        };
+        let idx = self.byte_idx();
+        self.buffer.overwrite_nibble(idx, nibble, !self.hex_nibble);
+        if self.hex_nibble {
+            self.go_to_byte(idx.saturating_add(1));
+        } else {
+            self.hex_nibble = true;
+        }
+        self.set_needs_redraw(true);
+    }
+
+    fn draw_hex(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+        let Size { height, width } = self.size;
+        for current_row in origin_row..origin_row.saturating_add(height) {
+            let row_idx = current_row
+                .saturating_sub(origin_row)
+                .saturating_add(self.scroll_offset.row);
+            if let Some(row) = self.buffer.hex_row(row_idx) {
+                let visible: String = row.chars().skip(self.scroll_offset.col).take(width).collect();
+                let element = if row_idx == self.text_location.line_idx {
+                    ThemeElement::CaretLine
+                } else {
+                    ThemeElement::Text
+                };
+                Terminal::print_themed_row(current_row, &visible, element)?;
+            } else {
+                Terminal::print_themed_row(current_row, "~", ThemeElement::Gutter)?;
+            }
+        }
+        Ok(())
+    }
+
     fn render_line(at: RowIdx, line_text: &str) -> Result<(), Error> {
         Terminal::print_row(at, line_text)
     }
@@ -268,13 +822,23 @@ impl View {
         self.set_needs_redraw(true);
     }
 
-    pub fn caret_position(&self) -> Position {
-        self.text_location_to_position()
-            .saturating_sub(self.scroll_offset)
+    pub fn caret_position(&self) -> Option<Position> {
+        let Size { height, width } = self.size;
+        let position = self.text_location_to_position();
+        let visible = position.row >= self.scroll_offset.row
+            && position.row < self.scroll_offset.row.saturating_add(height)
+            && position.col >= self.scroll_offset.col
+            && position.col < self.scroll_offset.col.saturating_add(width);
+        visible.then(|| position.saturating_sub(self.scroll_offset))
     }
 
     fn text_location_to_position(&self) -> Position {
         let row = self.text_location.line_idx;
+        if self.buffer.is_binary() {
+            let col = hexdump::column_of(self.text_location.grapheme_idx)
+                .saturating_add(usize::from(self.hex_nibble));
+            return Position { col, row };
+        }
         debug_assert!(row.saturating_sub(1) <= self.buffer.height());
         let col = self
             .buffer
@@ -284,15 +848,23 @@ impl View {
 
     fn move_up(&mut self, step: usize) {
         self.text_location.line_idx = self.text_location.line_idx.saturating_sub(step);
+        self.load_caret_line();
         self.snap_to_valid_grapheme();
     }
 
     fn move_down(&mut self, step: usize) {
         self.text_location.line_idx = self.text_location.line_idx.saturating_add(step);
+        self.load_caret_line();
         self.snap_to_valid_grapheme();
         self.snap_to_valid_line();
     }
 
+    /// Has the line of a large file the caret moved to read from disk.
+    fn load_caret_line(&mut self) {
+        let line_idx = self.text_location.line_idx;
+        self.buffer.load_lines(line_idx..line_idx.saturating_add(1));
+    }
+
     #[allow(clippy::arithmetic_side_effects)]
     fn move_right(&mut self) {
         let grapheme_count = self.buffer.grapheme_count(self.text_location.line_idx);
@@ -349,10 +921,16 @@ impl UIComponent for View {
     }
 
     fn draw(&mut self, origin_row: RowIdx) -> Result<(), Error> {
+        if self.buffer.is_binary() {
+            return self.draw_hex(origin_row);
+        }
         let Size { height, width } = self.size;
         let end_y = origin_row.saturating_add(height);
         let top_third = height.div_ceil(3);
         let scroll_top = self.scroll_offset.row;
+        let scroll_bottom = min(scroll_top.saturating_add(height), self.buffer.height());
+        self.buffer.load_lines(scroll_top..scroll_bottom);
+        self.buffer.highlight_syntax(scroll_bottom);
 
         let query = self
             .search_info
@@ -363,10 +941,11 @@ impl UIComponent for View {
         let mut highlighter = Highlighter::new(
             query, 
             selected_match,
-            self.buffer.get_file_info().get_file_type(),
+            self.selection(),
+            self.buffer.syntax_highlighter(),
         );
 
-        for current_row in 0..end_y.saturating_add(scroll_top) {
+        for current_row in scroll_top..scroll_bottom {
             self.buffer.highlight(current_row, &mut highlighter);
         }
 
@@ -380,11 +959,16 @@ impl UIComponent for View {
             self.buffer
             .get_highlighted_substring(line_idx, left..right, &highlighter) 
             {
-                Terminal::print_annotated_row(current_row, &annotated_string)?;
+                let element = if line_idx == self.text_location.line_idx {
+                    ThemeElement::CaretLine
+                } else {
+                    ThemeElement::Text
+                };
+                Terminal::print_annotated_row(current_row, &annotated_string, element)?;
             } else if current_row == top_third && self.buffer.is_empty() {
                 Self::render_line(current_row, &Self::build_welcome_message(width))?;
             } else {
-                Self::render_line(current_row, "~")?;
+                Terminal::print_themed_row(current_row, "~", ThemeElement::Gutter)?;
             }
         }
         Ok(())
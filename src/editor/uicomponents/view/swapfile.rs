@@ -0,0 +1,40 @@
+use std::{
+    fs,
+    io::Error,
+    path::{Path, PathBuf},
+};
+
+use super::atomicwrite::write_atomically_like;
+
+/// Where the unsaved contents of `file` are kept in case the editor dies:
+/// a hidden file next to it, e.g. `.notes.txt.hecto-swp` for `notes.txt`.
+/// Vim's `.notes.txt.swp` is left alone.
+#[must_use]
+pub fn path_for(file: &Path) -> Option<PathBuf> {
+    let file_name = file.file_name()?;
+    Some(file.with_file_name(format!(".{}.hecto-swp", file_name.to_string_lossy())))
+}
+
+/// Writes the swap file for `file`. It gets the file's permissions, so it
+/// does not expose more of the contents than the file itself.
+///
+/// # Errors
+///
+/// Returns an error if the swap file cannot be written.
+pub fn write(file: &Path, contents: &str) -> Result<(), Error> {
+    let Some(swap_path) = path_for(file) else {
+        return Ok(());
+    };
+    write_atomically_like(&swap_path, contents.as_bytes(), file)
+}
+
+#[must_use]
+pub fn read(file: &Path) -> Option<String> {
+    path_for(file).and_then(|swap_path| fs::read_to_string(swap_path).ok())
+}
+
+pub fn remove(file: &Path) {
+    if let Some(swap_path) = path_for(file) {
+        let _ = fs::remove_file(swap_path);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+//! The hecto text editor as a library.
+//!
+//! [`Editor`] runs the whole editor on the terminal. The rest is meant for
+//! tools that work with text the way hecto does:
+//!
+//! - [`Buffer`] holds a document as [`Line`]s and loads and saves it, keeping
+//!   its [`Encoding`] and [`LineEnding`]s. `Buffer::search_forward` and
+//!   `Buffer::search_backward` find text from a [`Location`], and the
+//!   methods of the same name on [`Line`] search within a line.
+//! - [`create_syntax_highlight`] picks the [`SyntaxHighlighter`] for a
+//!   [`FileType`]. It marks up lines with [`Annotation`]s, which an
+//!   [`AnnotatedString`] splits into [`AnnotatedStringPart`]s.
+#![warn(clippy::all, clippy::pedantic,clippy::print_stdout,clippy::arithmetic_side_effects,clippy::as_conversions,clippy::integer_division)]
+mod editor;
+mod prelude;
+
+pub use editor::{
+    create_syntax_highlight, AnnotatedString, AnnotatedStringPart, Annotation, AnnotationType,
+    Buffer, Editor, Encoding, FileOptions, FileType, Line, LineEnding, SyntaxHighlighter,
+};
+pub use prelude::{ByteIdx, ColIdx, GraphemeIdx, LineIdx, Location, NAME, VERSION};
\ No newline at end of file
@@ -1,8 +1,322 @@
 #![warn(clippy::all, clippy::pedantic,clippy::print_stdout,clippy::arithmetic_side_effects,clippy::as_conversions,clippy::integer_division)]
-mod editor;
-use editor::Editor;
-mod prelude;
+use std::{
+    env,
+    io::{stderr, stdin, stdout, Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use hecto::{Editor, FileOptions, FileType, Location, NAME, VERSION};
+
+const USAGE: &str = "\
+Usage: hecto [OPTIONS] [[+LINE] FILE[:LINE[:COL]]]...
+
+Opens each FILE for editing; Ctrl-N and Ctrl-P switch between them.
+A FILE of - reads the text to edit from standard input.
+
+Options:
+  +LINE            put the caret on LINE of the next file
+  --readonly, -R   open the files read-only
+  --pipe           write the current file to standard output on quit,
+                   reading standard input if no FILE is given
+  --config PATH    read the configuration from PATH
+  --syntax LANG    highlight the files as LANG, e.g. rust, toml, python
+  --help, -h       print this help and exit
+  --version, -V    print the version and exit";
+
+enum Source {
+    File(String),
+    Stdin,
+}
+
+#[derive(Default)]
+struct Args {
+    files: Vec<(Source, Location)>,
+    read_only: bool,
+    pipe: bool,
+    config: Option<PathBuf>,
+    file_type: Option<FileType>,
+}
+
+enum Action {
+    Edit(Args),
+    Help,
+    Version,
+}
 
 fn main() {
-    Editor::new().unwrap().run();
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Action::Edit(args)) => args,
+        Ok(Action::Help) => {
+            let _ = writeln!(stdout(), "{USAGE}");
+            return;
+        }
+        Ok(Action::Version) => {
+            let _ = writeln!(stdout(), "{NAME} {VERSION}");
+            return;
+        }
+        Err(err) => {
+            let _ = writeln!(stderr(), "{NAME}: {err}\nTry '{NAME} --help' for more information.");
+            process::exit(2);
+        }
+    };
+
+    // Piped input has to be read before the terminal takes over.
+    let mut piped = Vec::new();
+    if args.files.iter().any(|(source, _)| matches!(source, Source::Stdin)) {
+        if let Err(err) = stdin().read_to_end(&mut piped) {
+            let _ = writeln!(stderr(), "{NAME}: could not read standard input: {err}");
+            process::exit(1);
+        }
+    }
+
+    let mut editor = match Editor::with_config_file(args.config.as_deref()) {
+        Ok(editor) => editor,
+        Err(err) => {
+            let _ = writeln!(stderr(), "{NAME}: could not set up the terminal: {err}");
+            process::exit(1);
+        }
+    };
+    for (source, at) in &args.files {
+        let options = FileOptions {
+            at: *at,
+            read_only: args.read_only,
+            file_type: args.file_type,
+        };
+        match source {
+            Source::File(file_name) => editor.open_with(file_name, options),
+            Source::Stdin => editor.open_bytes(piped.clone(), options),
+        }
+    }
+    editor.set_pipe_mode(args.pipe);
+    editor.run();
+
+    if args.pipe {
+        // Restores the terminal before anything reaches standard output.
+        if let Err(err) = editor.write_text(&mut stdout().lock()) {
+            let _ = writeln!(stderr(), "{NAME}: could not write standard output: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Action, String> {
+    let mut parsed = Args::default();
+    let mut next_at = None;
+    let mut only_files = false;
+    let mut read_stdin = false;
+
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if arg.starts_with("--") && !only_files => {
+                (name.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        match name.as_str() {
+            _ if only_files => {}
+            "--" | "--help" | "--version" | "--readonly" | "--pipe" if inline_value.is_some() => {
+                return Err(format!("'{name}' takes no value"));
+            }
+            "--" => {
+                only_files = true;
+                continue;
+            }
+            "--help" | "-h" => return Ok(Action::Help),
+            "--version" | "-V" => return Ok(Action::Version),
+            "--readonly" | "-R" => {
+                parsed.read_only = true;
+                continue;
+            }
+            "--pipe" => {
+                parsed.pipe = true;
+                continue;
+            }
+            "--config" => {
+                let path = PathBuf::from(option_value(&name, inline_value, &mut args)?);
+                if !path.is_file() {
+                    return Err(format!("no configuration file at '{}'", path.display()));
+                }
+                parsed.config = Some(path);
+                continue;
+            }
+            "--syntax" => {
+                let value = option_value(&name, inline_value, &mut args)?;
+                parsed.file_type = Some(
+                    FileType::from_name(&value)
+                        .ok_or_else(|| format!("unknown syntax '{value}'"))?,
+                );
+                continue;
+            }
+            "-" => {
+                if read_stdin {
+                    return Err("standard input can only be read once".to_string());
+                }
+                read_stdin = true;
+                parsed.files.push((Source::Stdin, next_at.take().unwrap_or_default()));
+                continue;
+            }
+            _ if arg.starts_with('+') => {
+                let line = parse_number(&arg[1..])
+                    .ok_or_else(|| format!("expected a line number after '+', found '{arg}'"))?;
+                next_at = Some(Location {
+                    line_idx: line.saturating_sub(1),
+                    grapheme_idx: 0,
+                });
+                continue;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => {}
+        }
+
+        let (file_name, at) = split_position(&arg);
+        let at = next_at.take().or(at).unwrap_or_default();
+        parsed.files.push((Source::File(file_name.to_string()), at));
+    }
+
+    if next_at.is_some() {
+        return Err("'+LINE' must be followed by a file".to_string());
+    }
+    if parsed.pipe && parsed.files.is_empty() {
+        parsed.files.push((Source::Stdin, Location::default()));
+    }
+    Ok(Action::Edit(parsed))
+}
+
+fn option_value(
+    name: &str,
+    inline_value: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    inline_value
+        .or_else(|| args.next())
+        .ok_or_else(|| format!("'{name}' needs a value"))
+}
+
+/// Splits `file:line` or `file:line:col`, as printed by compilers, into the
+/// file and the caret location. A file that exists under the full name
+/// wins.
+fn split_position(arg: &str) -> (&str, Option<Location>) {
+    if Path::new(arg).exists() {
+        return (arg, None);
+    }
+    let mut parts = arg.rsplitn(3, ':');
+    let last = parts.next().and_then(parse_number);
+    let second_last = parts.next();
+    match (last, second_last.and_then(parse_number), parts.next()) {
+        (Some(col), Some(line), Some(file_name)) if !file_name.is_empty() => (
+            file_name,
+            Some(Location {
+                line_idx: line.saturating_sub(1),
+                grapheme_idx: col.saturating_sub(1),
+            }),
+        ),
+        (Some(line), _, _) => match arg.rsplit_once(':') {
+            Some((file_name, _)) if !file_name.is_empty() => (
+                file_name,
+                Some(Location {
+                    line_idx: line.saturating_sub(1),
+                    grapheme_idx: 0,
+                }),
+            ),
+            _ => (arg, None),
+        },
+        _ => (arg, None),
+    }
 }
+
+fn parse_number(value: &str) -> Option<usize> {
+    value.parse().ok().filter(|number| *number > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        match parse_args(args.iter().map(ToString::to_string))? {
+            Action::Edit(args) => Ok(args),
+            Action::Help => Err("help".to_string()),
+            Action::Version => Err("version".to_string()),
+        }
+    }
+
+    fn files(args: &[&str]) -> Vec<(String, usize, usize)> {
+        parse(args)
+            .unwrap()
+            .files
+            .into_iter()
+            .map(|(source, at)| {
+                let name = match source {
+                    Source::File(file_name) => file_name,
+                    Source::Stdin => "-".to_string(),
+                };
+                (name, at.line_idx, at.grapheme_idx)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plus_line_applies_to_the_next_file() {
+        assert_eq!(
+            files(&["+3", "a.txt", "b.txt"]),
+            [("a.txt".to_string(), 2, 0), ("b.txt".to_string(), 0, 0)]
+        );
+        assert!(parse(&["a.txt", "+3"]).is_err());
+        assert!(parse(&["+x", "a.txt"]).is_err());
+    }
+
+    #[test]
+    fn positions_after_the_file_name() {
+        assert_eq!(files(&["no/such.rs:4:7"]), [("no/such.rs".to_string(), 3, 6)]);
+        assert_eq!(files(&["no/such.rs:4"]), [("no/such.rs".to_string(), 3, 0)]);
+        assert_eq!(files(&["no/such.rs:x"]), [("no/such.rs:x".to_string(), 0, 0)]);
+        assert_eq!(files(&["+2", "no/such.rs:4:7"]), [("no/such.rs".to_string(), 1, 0)]);
+    }
+
+    #[test]
+    fn existing_files_win_over_positions() {
+        let (file_name, at) = split_position(".");
+        assert_eq!(file_name, ".");
+        assert!(at.is_none());
+        let (file_name, at) = split_position(":5");
+        assert_eq!(file_name, ":5");
+        assert!(at.is_none());
+    }
+
+    #[test]
+    fn double_dash_ends_the_options() {
+        assert_eq!(
+            files(&["--", "--pipe", "-", "+3"]),
+            [
+                ("--pipe".to_string(), 0, 0),
+                ("-".to_string(), 0, 0),
+                ("+3".to_string(), 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn option_values_inline_or_separate() {
+        assert_eq!(parse(&["--syntax=python"]).unwrap().file_type, Some(FileType::Python));
+        assert_eq!(parse(&["--syntax", "python"]).unwrap().file_type, Some(FileType::Python));
+        assert_eq!(parse(&["--syntax"]).err().unwrap(), "'--syntax' needs a value");
+        assert_eq!(parse(&["--syntax=cobol"]).err().unwrap(), "unknown syntax 'cobol'");
+    }
+
+    #[test]
+    fn flags_take_no_value() {
+        assert!(parse(&["--readonly"]).unwrap().read_only);
+        assert_eq!(parse(&["--readonly=x"]).err().unwrap(), "'--readonly' takes no value");
+        assert_eq!(parse(&["--pipe=yes"]).err().unwrap(), "'--pipe' takes no value");
+        assert_eq!(parse(&["--help=x"]).err().unwrap(), "'--help' takes no value");
+    }
+
+    #[test]
+    fn pipe_reads_standard_input_without_files() {
+        let args = parse(&["--pipe"]).unwrap();
+        assert!(args.pipe);
+        assert!(matches!(args.files.as_slice(), [(Source::Stdin, _)]));
+        assert!(parse(&["-", "-"]).is_err());
+    }
+}
\ No newline at end of file
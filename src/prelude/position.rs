@@ -1,11 +1,12 @@
 use super::{ColIdx,RowIdx};
-#[derive(Clone, Copy,Default)]
+#[derive(Clone, Copy,Default,PartialEq,Eq,Debug)]
 pub struct Position {
     pub col: ColIdx,
     pub row: RowIdx,
 }
 
 impl Position {
+    #[must_use]
     pub const fn saturating_sub(self,other: Self) -> Self {
         Self {
             row: self.row.saturating_sub(other.row),
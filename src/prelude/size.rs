@@ -1,4 +1,4 @@
-#[derive(Default,Clone, Copy,PartialEq,Eq)]
+#[derive(Default,Clone, Copy,PartialEq,Eq,Debug)]
 pub struct Size {
     pub height: usize,
     pub width: usize,